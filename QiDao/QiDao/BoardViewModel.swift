@@ -60,7 +60,7 @@ enum MarkerType {
 class BoardViewModel: ObservableObject {
     @Published var message: String = "Ready".localized
     @Published var gameInfo: String = ""
-    @Published var board: Board = Board(size: 19)
+    @Published var board: Board = try! Board(size: 19)
     @Published var nextColor: StoneColor = .black
     @Published var theme: BoardTheme = .defaultWood
     @Published var moveNumberDisplay: MoveNumberDisplay = .all {
@@ -177,7 +177,7 @@ class BoardViewModel: ObservableObject {
         komi: 7.5, result: "",
         date: "", event: "",
         gameName: "", place: "",
-        size: 19
+        size: 19, width: 19, height: 19
     )
 
     private var nodeMap: [String: SgfNode] = [:]
@@ -224,7 +224,7 @@ class BoardViewModel: ObservableObject {
 
     init() {
         // Initialize Game Controller
-        self.game = Game(size: 19)
+        self.game = try! Game(size: 19)
 
         // Load persisted settings
         if let rawValue = UserDefaults.standard.object(forKey: "moveNumberDisplay") as? Int,
@@ -900,7 +900,7 @@ class BoardViewModel: ObservableObject {
     func resetBoard() {
         fullGameScanTask?.cancel()
         fullGameScanTask = nil
-        self.game = Game(size: 19)
+        self.game = try! Game(size: 19)
         self.winRateHistory = [:]
         self.scoreLeadHistory = [:]
         self.blunders = [:]
@@ -950,7 +950,7 @@ class BoardViewModel: ObservableObject {
             self.analysisResult = nil
 
             // Update main line colors for win rate normalization
-            let mainLine = game.getMainLineMoves()
+            let mainLine = (try? game.getMainLineMoves()) ?? []
             self.mainLineColors = [:]
             for (i, m) in mainLine.enumerated() {
                 if m.count >= 1 {
@@ -973,8 +973,8 @@ class BoardViewModel: ObservableObject {
     private func startFullGameAnalysis() {
         guard isAnalyzing, let engine = analysisEngine else { return }
 
-        let mainLineMoves = game.getMainLineMoves()
-        let initialStones = game.getInitialStones()
+        guard let mainLineMoves = try? game.getMainLineMoves(),
+              let initialStones = try? game.getInitialStones() else { return }
         if mainLineMoves.isEmpty && initialStones.isEmpty { return }
 
         // Update main line colors for win rate normalization
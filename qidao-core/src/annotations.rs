@@ -0,0 +1,125 @@
+use crate::properties::{raw_property, typed_property};
+use crate::{set_property, Annotation, Game, Property, SgfNode, MAX_BOARD_SIZE};
+
+/// An annotation on a node; `emphasized` is the SGF "very" (value 2) and is always false
+/// for doubtful and interesting moves.
+#[derive(uniffi::Record, Clone, Debug, PartialEq, Eq)]
+pub struct NodeAnnotation {
+    pub annotation: Annotation,
+    pub emphasized: bool,
+}
+
+/// Move quality (BM, TE, DO, IT); a node carries at most one.
+const MOVE_QUALITY: [Annotation; 4] =
+    [Annotation::BadMove, Annotation::Tesuji, Annotation::Doubtful, Annotation::Interesting];
+
+/// Position judgements (GB, GW, DM, UC); a node carries at most one.
+const JUDGEMENT: [Annotation; 4] =
+    [Annotation::GoodForBlack, Annotation::GoodForWhite, Annotation::Even, Annotation::Unclear];
+
+#[uniffi::export]
+impl SgfNode {
+    /// The `C` comment, or an empty string.
+    pub fn get_comment(&self) -> String {
+        self.text_property("C")
+    }
+
+    /// Sets the `C` comment; empty text removes it.
+    pub fn set_comment(&self, text: String) {
+        self.set_text_property("C", text);
+    }
+
+    /// The `N` node name, or an empty string.
+    pub fn get_node_name(&self) -> String {
+        self.text_property("N")
+    }
+
+    /// Sets the `N` node name; empty text removes it. Names are simple text, so line
+    /// breaks and tabs become spaces.
+    pub fn set_node_name(&self, name: String) {
+        self.set_text_property("N", name.replace("\r\n", " ").replace(['\n', '\r', '\t'], " "));
+    }
+
+    /// Move quality, position judgement and hotspot annotations, in stored order.
+    pub fn get_annotations(&self) -> Vec<NodeAnnotation> {
+        self.properties
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|p| match typed_property(p.clone(), MAX_BOARD_SIZE, MAX_BOARD_SIZE) {
+                Property::Annotation { annotation, emphasized } => Some(NodeAnnotation { annotation, emphasized }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Adds an annotation, replacing any other move quality or position judgement.
+    pub fn set_annotation(&self, annotation: Annotation, emphasized: bool) {
+        let group = [MOVE_QUALITY, JUDGEMENT].into_iter().find(|g| g.contains(&annotation));
+        for other in group.into_iter().flatten().filter(|&a| a != annotation) {
+            self.remove_annotation(other);
+        }
+        let raw = raw_property(Property::Annotation { annotation, emphasized });
+        set_property(&mut self.properties.lock().unwrap(), &raw.identifier, raw.values);
+    }
+
+    pub fn remove_annotation(&self, annotation: Annotation) {
+        let id = raw_property(Property::Annotation { annotation, emphasized: false }).identifier;
+        self.properties.lock().unwrap().retain(|p| p.identifier != id);
+    }
+}
+
+impl SgfNode {
+    fn text_property(&self, id: &str) -> String {
+        let props = self.properties.lock().unwrap();
+        props
+            .iter()
+            .find(|p| p.identifier == id)
+            .map(|p| p.values.concat())
+            .unwrap_or_default()
+    }
+
+    fn set_text_property(&self, id: &str, text: String) {
+        let mut props = self.properties.lock().unwrap();
+        if text.is_empty() {
+            props.retain(|p| p.identifier != id);
+        } else {
+            set_property(&mut props, id, vec![text]);
+        }
+    }
+}
+
+#[uniffi::export]
+impl Game {
+    /// Comment of the current node, or an empty string.
+    pub fn get_comment(&self) -> String {
+        self.get_current_node().get_comment()
+    }
+
+    /// Sets the comment of the current node; empty text removes it.
+    pub fn set_comment(&self, text: String) {
+        self.get_current_node().set_comment(text);
+    }
+
+    pub fn get_node_name(&self) -> String {
+        self.get_current_node().get_node_name()
+    }
+
+    pub fn set_node_name(&self, name: String) {
+        self.get_current_node().set_node_name(name);
+    }
+
+    /// Annotations of the current node.
+    pub fn get_annotations(&self) -> Vec<NodeAnnotation> {
+        self.get_current_node().get_annotations()
+    }
+
+    /// Annotates the current node, replacing any other move quality or position judgement.
+    pub fn set_annotation(&self, annotation: Annotation, emphasized: bool) {
+        self.get_current_node().set_annotation(annotation, emphasized);
+    }
+
+    pub fn remove_annotation(&self, annotation: Annotation) {
+        self.get_current_node().remove_annotation(annotation);
+    }
+}
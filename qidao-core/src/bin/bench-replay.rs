@@ -0,0 +1,86 @@
+use qidao_core::{parse_move, Board, Game, Move, Notation, StoneColor};
+use std::path::PathBuf;
+use std::time::Instant;
+
+// Usage: cargo run --release --bin bench-replay [games-dir] [iterations]
+
+/// A main line ready to replay: board width, height and the moves in order.
+type Line = (u32, u32, Vec<(StoneColor, Move)>);
+
+fn visit_all(game: &Game) -> u64 {
+    game.get_board();
+    let mut boards = 1;
+    for i in 0..game.get_current_node().get_children().len() {
+        game.go_forward(i as u32);
+        boards += visit_all(game);
+        game.go_back();
+    }
+    boards
+}
+
+fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let dir = args
+        .next()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../assets/games"));
+    let iterations: u32 = args.next().map(|n| n.parse()).transpose()?.unwrap_or(200);
+
+    let mut games = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == "sgf") {
+            games.push((path.clone(), std::fs::read_to_string(&path)?));
+        }
+    }
+    games.sort();
+    println!("Replaying {} games from {} ({} iterations)", games.len(), dir.display(), iterations);
+
+    // Every node of every variation, through Game::get_board on a freshly loaded game
+    let start = Instant::now();
+    let mut boards = 0;
+    for _ in 0..iterations {
+        for (_, sgf) in &games {
+            let game = Game::from_sgf(sgf.clone())?;
+            boards += visit_all(&game);
+        }
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+    println!("Game tree replay: {} boards in {:.3}s ({:.0} boards/s)", boards, elapsed, boards as f64 / elapsed);
+
+    // Main lines played directly on Board
+    let lines: Vec<Line> = games
+        .iter()
+        .map(|(_, sgf)| {
+            let game = Game::from_sgf(sgf.clone())?;
+            let meta = game.get_metadata();
+            let moves = game
+                .get_main_line_moves()?
+                .into_iter()
+                .map(|mv| {
+                    let color = if mv[0] == "B" { StoneColor::Black } else { StoneColor::White };
+                    Ok((color, parse_move(mv[1].clone(), Notation::Gtp, meta.width, meta.height)?))
+                })
+                .collect::<Result<Vec<_>, qidao_core::SgfError>>()?;
+            Ok((meta.width, meta.height, moves))
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    let start = Instant::now();
+    let mut moves = 0;
+    for _ in 0..iterations {
+        for (width, height, line) in &lines {
+            let mut board = Board::new_rectangular(*width, *height)?;
+            for (color, mv) in line {
+                board = match mv {
+                    Move::Play { point } => board.place_stone(point.x, point.y, *color).unwrap_or(board),
+                    Move::Pass => board.pass(),
+                };
+                moves += 1;
+            }
+        }
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+    println!("Board replay:     {} moves in {:.3}s ({:.0} moves/s)", moves, elapsed, moves as f64 / elapsed);
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use qidao_core::{
+    default_diagram_options, kifu_format_for_extension, print_diagram_theme, DiagramRegion, Game, MoveRange,
+    StoneStyle,
+};
+
+// Usage: cargo run --bin sgf-diagram -- <game-file> [--move N] [--numbers FIRST-LAST]
+//            [--region LEFT,TOP,RIGHT,BOTTOM] [--print] [--flat] [--no-coordinates] > diagram.svg
+// Any format `Game::from_kifu` reads works; the position is at move N of the main line.
+
+fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let path = args.next().ok_or_else(|| anyhow::anyhow!("missing game file"))?;
+    let mut options = default_diagram_options();
+    let mut move_number = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow::anyhow!("{} needs a value", arg));
+        match arg.as_str() {
+            "--move" => move_number = Some(value()?.parse::<u32>()?),
+            "--numbers" => {
+                let value = value()?;
+                let (first, last) = value.split_once('-').ok_or_else(|| anyhow::anyhow!("expected FIRST-LAST"))?;
+                options.move_range = Some(MoveRange { first: first.parse()?, last: last.parse()? });
+            }
+            "--region" => {
+                let bounds = value()?.split(',').map(str::parse).collect::<Result<Vec<u32>, _>>()?;
+                let [left, top, right, bottom] = bounds[..] else { anyhow::bail!("expected LEFT,TOP,RIGHT,BOTTOM") };
+                options.region = Some(DiagramRegion { left, top, right, bottom });
+            }
+            "--print" => options.theme = print_diagram_theme(),
+            "--flat" => options.stone_style = StoneStyle::Flat,
+            "--no-coordinates" => options.coordinates = false,
+            _ => anyhow::bail!("unknown option {}", arg),
+        }
+    }
+
+    let format = path.rsplit('.').next().and_then(|ext| kifu_format_for_extension(ext.to_string()));
+    let game = Game::from_kifu_bytes(std::fs::read(&path)?, format)?;
+    // Numbered moves are shown up to the end of the range unless a move is given
+    let target = move_number.or(options.move_range.map(|r| r.last)).unwrap_or(u32::MAX);
+    game.jump_to_move_number(target.min(game.get_max_move_count()));
+    println!("{}", game.to_svg(options));
+    Ok(())
+}
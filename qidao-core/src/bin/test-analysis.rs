@@ -33,7 +33,7 @@ fn main() -> anyhow::Result<()> {
     });
 
     println!("Sending query: {}", query);
-    writeln!(stdin, "{}", query.to_string())?;
+    writeln!(stdin, "{}", query)?;
     stdin.flush()?;
 
     let mut response = String::new();
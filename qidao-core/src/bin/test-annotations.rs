@@ -0,0 +1,72 @@
+//! Reads and edits comments, node names and annotations on the nodes of a small game and
+//! checks what is saved, including escaped text and soft line breaks.
+//!
+//! Usage: cargo run --bin test-annotations
+
+use qidao_core::{Annotation, Game, NodeAnnotation};
+
+fn a(annotation: Annotation, emphasized: bool) -> NodeAnnotation {
+    NodeAnnotation { annotation, emphasized }
+}
+
+fn main() {
+    let mut failures = Vec::new();
+    let game = Game::from_sgf("(;SZ[9]C[root];B[cc]C[a \\\nsoft break]N[Joseki]BM[2]GB[1];W[dd]DO[]HO[1])".to_string())
+        .unwrap();
+
+    // Reading: the soft line break is dropped, the "very" value is kept
+    game.jump_to_move_number(1);
+    if game.get_comment() != "a soft break" || game.get_node_name() != "Joseki" {
+        failures.push(format!("read {:?} named {:?}", game.get_comment(), game.get_node_name()));
+    }
+    if game.get_annotations() != [a(Annotation::BadMove, true), a(Annotation::GoodForBlack, false)] {
+        failures.push(format!("move 1 annotations {:?}", game.get_annotations()));
+    }
+    game.go_forward(0);
+    if game.get_annotations() != [a(Annotation::Doubtful, false), a(Annotation::Hotspot, false)] {
+        failures.push(format!("move 2 annotations {:?}", game.get_annotations()));
+    }
+
+    // A tesuji replaces the bad move and a judgement replaces the other; doubtful and
+    // interesting moves have no "very"
+    game.go_back();
+    game.set_annotation(Annotation::Tesuji, false);
+    game.set_annotation(Annotation::Unclear, true);
+    game.go_forward(0);
+    game.set_annotation(Annotation::Interesting, true);
+    game.remove_annotation(Annotation::Hotspot);
+    if game.get_annotations() != [a(Annotation::Interesting, false)] {
+        failures.push(format!("edited move 2 annotations {:?}", game.get_annotations()));
+    }
+
+    // Text with brackets, backslashes and hard line breaks is escaped and comes back as set;
+    // names stay on one line
+    let comment = "Black [should] play \\ here\nnot there";
+    game.set_comment(comment.to_string());
+    game.set_node_name("Two\nlines\tand a tab".to_string());
+    game.go_back();
+    game.set_comment(String::new());
+
+    let sgf = game.to_sgf();
+    let expected = [";B[cc]N[Joseki]TE[1]UC[2];", "C[Black [should\\] play \\\\ here\nnot there]", "IT[]", "N[Two lines and a tab]"];
+    for written in expected {
+        if !sgf.contains(written) {
+            failures.push(format!("{} missing from {}", written, sgf));
+        }
+    }
+    let reloaded = Game::from_sgf(sgf.clone()).unwrap();
+    reloaded.jump_to_move_number(2);
+    if reloaded.get_comment() != comment || reloaded.get_annotations() != [a(Annotation::Interesting, false)] {
+        failures.push(format!("reloaded as {:?}", reloaded.get_comment()));
+    }
+    if reloaded.get_root_node().get_comment() != "root" {
+        failures.push("the root comment changed".into());
+    }
+
+    if failures.is_empty() {
+        println!("ok    comments, names and annotations");
+    } else {
+        eprintln!("FAIL  {}", failures.join("; "));
+        std::process::exit(1);
+    }
+}
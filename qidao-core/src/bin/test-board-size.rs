@@ -0,0 +1,61 @@
+//! Checks the board size limits of the constructors and that rectangular and 52x52 boards
+//! load, play and save with valid SGF coordinates.
+//!
+//! Usage: cargo run --bin test-board-size
+
+use qidao_core::{default_diagram_options, Board, Game, StoneColor, MAX_BOARD_SIZE};
+
+fn main() {
+    let mut failures = Vec::new();
+
+    let max = MAX_BOARD_SIZE;
+    for (width, height) in [(0, 19), (19, 0), (0, 0), (max + 1, 19), (19, max + 1)] {
+        if Board::new_rectangular(width, height).is_ok() || Game::new_rectangular(width, height).is_ok() {
+            failures.push(format!("{}x{} accepted", width, height));
+        }
+    }
+    for size in [0, max + 1] {
+        if Board::new(size).is_ok() || Game::new(size).is_ok() || Game::with_handicap(size, 2).is_ok() {
+            failures.push(format!("size {} accepted", size));
+        }
+    }
+    for (width, height) in [(1, 1), (1, max), (max, max), (19, 13)] {
+        match Board::new_rectangular(width, height) {
+            // A one-point board still draws
+            Ok(board) if board.get_width() == width && board.get_height() == height => {
+                board.to_svg(default_diagram_options());
+            }
+            _ => failures.push(format!("{}x{} refused", width, height)),
+        }
+        if Game::new_rectangular(width, height).is_err() {
+            failures.push(format!("{}x{} game refused", width, height));
+        }
+    }
+
+    // The far corner of the largest board uses SGF's uppercase letters and reloads there
+    let game = Game::new(max).unwrap();
+    game.place_stone(max - 1, max - 1, StoneColor::Black).unwrap();
+    let sgf = game.to_sgf();
+    if !sgf.contains("SZ[52]") || !sgf.contains("B[ZZ]") {
+        failures.push(format!("52x52 saved as {}", sgf));
+    }
+    let reloaded = Game::from_sgf(sgf).unwrap();
+    reloaded.jump_to_move_number(1);
+    if reloaded.get_board().get_stone(max - 1, max - 1) != Some(StoneColor::Black) {
+        failures.push("52x52 corner stone lost on reload".into());
+    }
+
+    // SZ gives the width first
+    let rectangle = Game::new_rectangular(19, 13).unwrap();
+    let meta = Game::from_sgf(rectangle.to_sgf()).unwrap().get_metadata();
+    if !rectangle.to_sgf().contains("SZ[19:13]") || (meta.width, meta.height) != (19, 13) {
+        failures.push(format!("19x13 reloaded as {}x{}", meta.width, meta.height));
+    }
+
+    if failures.is_empty() {
+        println!("ok    sizes 1 to {} accepted, 0 and {} refused", max, max + 1);
+    } else {
+        eprintln!("FAIL  {}", failures.join("; "));
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,100 @@
+//! Plays captures and multi-stone suicide on small positions and checks the captured points
+//! and the prisoner counts, on `Board` and along a `Game` path.
+//!
+//! Usage: cargo run --bin test-captures
+
+use qidao_core::{Game, Point, Prisoners, StoneColor};
+
+use StoneColor::{Black, White};
+
+/// (name, position, move, captured points, prisoners after the move)
+type Case = (&'static str, &'static str, (u32, u32, StoneColor), Vec<(u32, u32)>, Prisoners);
+
+fn cases() -> Vec<Case> {
+    vec![
+        ("one stone", "(;SZ[5]AB[ab][ba][cb]AW[bb])", (1, 2, Black), vec![(1, 1)], Prisoners { black: 1, white: 0 }),
+        (
+            "two stones",
+            "(;SZ[5]AB[ab][ba][ca][db][bc]AW[bb][cb])",
+            (2, 2, Black),
+            vec![(1, 1), (2, 1)],
+            Prisoners { black: 2, white: 0 },
+        ),
+        ("no capture", "(;SZ[5]AB[ab][ba]AW[bb])", (2, 2, White), vec![], Prisoners::default()),
+        // New Zealand rules allow the suicide; the two white stones go to Black
+        (
+            "suicide",
+            "(;SZ[5]RU[NZ]AB[ca][bb][ab]AW[aa])",
+            (1, 0, White),
+            vec![(0, 0), (1, 0)],
+            Prisoners { black: 2, white: 0 },
+        ),
+    ]
+}
+
+fn main() {
+    let mut failures = 0;
+    for (name, sgf, (x, y, color), captured, prisoners) in cases() {
+        let mut problems = Vec::new();
+        let game = Game::from_sgf(sgf.to_string()).expect("case parses");
+        let expected: Vec<Point> = captured.iter().map(|&(x, y)| Point { x, y }).collect();
+
+        match game.get_board().play(x, y, color) {
+            Ok(result) if result.captured == expected && result.board.get_prisoners() == prisoners => {}
+            Ok(result) => problems.push(format!("board captured {:?}, {:?}", result.captured, result.board.get_prisoners())),
+            Err(e) => problems.push(format!("board refused the move: {}", e)),
+        }
+        match game.play_move(x, y, color) {
+            Ok(result) if result.captured == expected && game.get_prisoners() == prisoners => {}
+            Ok(result) => problems.push(format!("game captured {:?}, {:?}", result.captured, game.get_prisoners())),
+            Err(e) => problems.push(format!("game refused the move: {}", e)),
+        }
+        if game.get_board().get_last_captures() != expected {
+            problems.push("captures are not kept on the board".to_string());
+        }
+
+        if problems.is_empty() {
+            println!("ok    {}", name);
+        } else {
+            failures += 1;
+            println!("FAIL  {:<16} {}", name, problems.join("; "));
+        }
+    }
+
+    // The same suicide is refused where the rules forbid it
+    let chinese = Game::from_sgf("(;SZ[5]AB[ca][bb][ab]AW[aa])".to_string()).unwrap();
+    if chinese.play_move(1, 0, White).is_ok() {
+        failures += 1;
+        println!("FAIL  suicide was played under Chinese rules");
+    }
+
+    // Prisoners are counted along the current path: White's capture on the main line is not
+    // counted on the other branch, and going back to the root drops Black's
+    let game = Game::from_sgf("(;SZ[5]AB[ab][ba][cb]AW[bb][dc][cd][ed];B[bc](;W[ee];B[dd];W[de])(;W[ae]))".to_string())
+        .unwrap();
+    let mut counts = Vec::new();
+    for line in [[0, 0, 0, 0], [0, 1, 0, 0]] {
+        game.jump_to_move_number(0);
+        counts.push(game.get_prisoners());
+        for index in line {
+            if game.go_forward(index) {
+                counts.push(game.get_prisoners());
+            }
+        }
+    }
+    game.go_back();
+    counts.push(game.get_prisoners());
+    let p = |black, white| Prisoners { black, white };
+    let expected = [p(0, 0), p(1, 0), p(1, 0), p(1, 0), p(1, 1), p(0, 0), p(1, 0), p(1, 0), p(1, 0)];
+    if counts != expected {
+        failures += 1;
+        println!("FAIL  path prisoners {:?}", counts);
+    } else {
+        println!("ok    path prisoners");
+    }
+
+    if failures > 0 {
+        eprintln!("{} case(s) failed", failures);
+        std::process::exit(1);
+    }
+}
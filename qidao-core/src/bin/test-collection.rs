@@ -0,0 +1,71 @@
+//! Loads a file with several game trees and checks that every game is kept, that games can
+//! be opened, edited, added, moved and removed, and what the collection writes back.
+//!
+//! Usage: cargo run --bin test-collection
+
+use qidao_core::{Game, GameMetadata, SgfCollection, StoneColor};
+
+const BUNDLE: &str = "(;GN[Round 1]SZ[19]PB[Ke Jie];B[pd];W[dp])\n\n(;GN[Round 2]SZ[13])  (;GN[Round 3]SZ[9]KM[7];B[ee])";
+
+fn names(collection: &SgfCollection) -> Vec<String> {
+    collection.get_all_metadata().into_iter().map(|m| m.game_name).collect()
+}
+
+fn main() {
+    let mut failures = Vec::new();
+    let collection = SgfCollection::from_sgf(BUNDLE.to_string()).unwrap();
+
+    let sizes: Vec<u32> = collection.get_all_metadata().iter().map(|m| m.size).collect();
+    if collection.get_game_count() != 3 || names(&collection) != ["Round 1", "Round 2", "Round 3"] || sizes != [19, 13, 9] {
+        failures.push(format!("read {:?} sized {:?}", names(&collection), sizes));
+    }
+    if collection.get_metadata(0).unwrap().black_name != "Ke Jie" || collection.get_game(0).unwrap().get_max_move_count() != 2 {
+        failures.push("the first game lost its content".into());
+    }
+
+    // Edits through an opened game are part of the collection
+    let second = collection.get_game(1).unwrap();
+    second.place_stone(3, 3, StoneColor::Black).unwrap();
+    if !collection.to_sgf().contains("(;GN[Round 2]SZ[13];B[dd])") {
+        failures.push(format!("edit not written: {}", collection.to_sgf()));
+    }
+
+    // Insert at the front and move it to the end, then take Round 2 out and append it
+    let new_game = Game::new(9).unwrap();
+    new_game.set_metadata(GameMetadata { game_name: "Extra".into(), ..new_game.get_metadata() });
+    collection.insert_game(0, new_game).unwrap();
+    collection.move_game(0, 3).unwrap();
+    let removed = collection.remove_game(1).unwrap();
+    collection.add_game(removed);
+    if names(&collection) != ["Round 1", "Round 3", "Extra", "Round 2"] {
+        failures.push(format!("reordered as {:?}", names(&collection)));
+    }
+
+    for (name, refused) in [
+        ("get", collection.get_game(4).is_err()),
+        ("insert", collection.insert_game(5, Game::new(9).unwrap()).is_err()),
+        ("remove", collection.remove_game(4).is_err()),
+        ("move", collection.move_game(0, 4).is_err()),
+    ] {
+        if !refused {
+            failures.push(format!("{} accepted index 4 or more", name));
+        }
+    }
+
+    // The written file reads back as the same games, one tree per line
+    let sgf = collection.to_sgf();
+    let reread = SgfCollection::from_sgf(sgf.clone()).unwrap();
+    if names(&reread) != names(&collection) || reread.to_sgf() != sgf || sgf.lines().count() != 4 {
+        failures.push(format!("written as {}", sgf));
+    }
+    if SgfCollection::new().to_sgf() != "" || SgfCollection::from_sgf("(;SZ[9])".into()).unwrap().get_game_count() != 1 {
+        failures.push("empty or single-game collection".into());
+    }
+
+    if failures.is_empty() {
+        println!("ok    read, edit, insert, move, remove and write");
+    } else {
+        eprintln!("FAIL  {}", failures.join("; "));
+        std::process::exit(1);
+    }
+}
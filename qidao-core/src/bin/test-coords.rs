@@ -0,0 +1,71 @@
+//! Reads and writes moves in each notation, checks that traditional coordinates count
+//! columns from the right and rows from the top, and that an unreadable move fails the
+//! engine query instead of being sent as a pass.
+//!
+//! Usage: cargo run --bin test-coords
+
+use qidao_core::{format_move, parse_move, Game, Notation};
+
+/// (text, notation, width, height, GTP vertex)
+type Case = (&'static str, Notation, u32, u32, &'static str);
+
+fn cases() -> Vec<Case> {
+    vec![
+        ("pd", Notation::Sgf, 19, 19, "Q16"),
+        ("", Notation::Sgf, 19, 19, "pass"),
+        ("tt", Notation::Sgf, 19, 19, "pass"),
+        ("(15,3)", Notation::KataGo, 19, 19, "Q16"),
+        ("4-4", Notation::Numeric, 19, 19, "Q16"),
+        ("16-4", Notation::Numeric, 19, 19, "D16"),
+        ("3-17", Notation::Numeric, 19, 19, "R3"),
+        ("1-1", Notation::Numeric, 13, 9, "N9"),
+        ("四の四", Notation::Cjk, 19, 19, "Q16"),
+        ("十六之四", Notation::Cjk, 19, 19, "D16"),
+        ("三之三", Notation::Cjk, 9, 9, "G7"),
+    ]
+}
+
+fn main() {
+    let mut failures = 0;
+    for (text, notation, width, height, vertex) in cases() {
+        let mut problems = Vec::new();
+        match parse_move(text.to_string(), notation, width, height) {
+            Ok(mv) => {
+                let gtp = format_move(mv, Notation::Gtp, width, height).unwrap_or_default();
+                if gtp != vertex {
+                    problems.push(format!("read as {}", gtp));
+                }
+                // の and 之 are both read; 之 is written
+                let back = format_move(mv, notation, width, height).unwrap_or_default();
+                if back.replace('の', "之") != text.replace('の', "之") && vertex != "pass" {
+                    problems.push(format!("written back as {}", back));
+                }
+            }
+            Err(e) => problems.push(e.to_string()),
+        }
+
+        let name = format!("{:?} {:?}", notation, text);
+        if problems.is_empty() {
+            println!("ok    {}", name);
+        } else {
+            failures += 1;
+            println!("FAIL  {:<20} {}", name, problems.join("; "));
+        }
+    }
+
+    for (name, sgf) in [("move off the board", "(;SZ[9];B[jj])"), ("setup off the board", "(;SZ[9]AB[aa][jj])")] {
+        let game = Game::from_sgf(sgf.to_string()).expect("case parses");
+        game.jump_to_move_number(game.get_max_move_count());
+        if game.get_analysis_query("q".into()).is_ok() {
+            failures += 1;
+            println!("FAIL  {:<20} sent to the engine", name);
+        } else {
+            println!("ok    {}", name);
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{} case(s) failed", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,65 @@
+//! Loads the encoding fixtures and checks that names and comments come out as UTF-8.
+//!
+//! Usage: cargo run --bin test-encodings [fixtures-dir]
+//! Defaults to `assets/encodings` next to the crate.
+
+use qidao_core::{decode_sgf_bytes, Game};
+
+/// (fixture stem, encoding expected to be detected, black player, white player)
+const FIXTURES: [(&str, &str, &str, &str); 7] = [
+    ("gb2312", "GBK", "柯洁", "芈昱廷"),
+    ("gbk", "GBK", "古力", "常昊"),
+    ("big5", "Big5", "林海峰", "王立誠"),
+    ("shift-jis", "Shift_JIS", "井山裕太", "一力遼"),
+    ("euc-kr", "EUC-KR", "이세돌", "박정환"),
+    ("utf8-bom", "UTF-8", "柯洁", "芈昱廷"),
+    ("utf8-mislabelled", "UTF-8", "柯洁", "芈昱廷"),
+];
+
+fn main() {
+    let dir = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| format!("{}/../assets/encodings", env!("CARGO_MANIFEST_DIR")));
+
+    let mut failures = 0;
+    for (stem, encoding, black, white) in FIXTURES {
+        // Legacy encodings are checked both with their CA and sniffed without it
+        let mut variants = vec![stem.to_string()];
+        if !stem.starts_with("utf8") {
+            variants.push(format!("{}-no-ca", stem));
+        }
+        for name in variants {
+            let path = format!("{}/{}.sgf", dir, name);
+            let bytes = std::fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+            let decoded = decode_sgf_bytes(bytes.clone());
+            let game = Game::from_sgf_bytes(bytes).expect("fixture parses");
+            let meta = game.get_metadata();
+            let saved = game.to_sgf();
+
+            let mut problems = Vec::new();
+            if decoded.encoding != encoding {
+                problems.push(format!("decoded as {}, expected {}", decoded.encoding, encoding));
+            }
+            if meta.black_name != black || meta.white_name != white {
+                problems.push(format!("players {} / {}", meta.black_name, meta.white_name));
+            }
+            // Anything but a UTF-8 file without CA is saved as declared UTF-8
+            let labelled = decoded.declared.is_some() || decoded.encoding != "UTF-8";
+            if labelled != saved.contains("CA[UTF-8]") {
+                problems.push(format!("saved {} CA[UTF-8]", if labelled { "without" } else { "with" }));
+            }
+
+            if problems.is_empty() {
+                println!("ok    {:<22} {:<10} {} vs {}", name, decoded.encoding, black, white);
+            } else {
+                failures += 1;
+                println!("FAIL  {:<22} {}", name, problems.join("; "));
+            }
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{} fixture(s) failed", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,90 @@
+//! Runs the engine-free estimator on small positions and checks the ownership layout and
+//! signs, the territory counts and the score under area and territory rules.
+//!
+//! Usage: cargo run --bin test-estimate
+
+use qidao_core::{Game, StoneColor};
+
+/// Black wall on the third column, White wall on the sixth.
+const WALLS: &str = "AB[ca][cb][cc][cd][ce][cf][cg][ch][ci]AW[fa][fb][fc][fd][fe][ff][fg][fh][fi]";
+
+/// (name, game, Black territory, White territory, score lead)
+type Case = (&'static str, String, u32, u32, f64);
+
+fn cases() -> Vec<Case> {
+    vec![
+        ("empty", "(;SZ[9]KM[7])".to_string(), 0, 0, -7.0),
+        // Area: 18 + 9 stones against 27 + 9 stones, less komi
+        ("walls, area", format!("(;SZ[9]KM[7]RU[Chinese]{})", WALLS), 18, 27, -16.0),
+        ("walls, territory", format!("(;SZ[9]KM[6.5]RU[Japanese]{})", WALLS), 18, 27, -15.5),
+        // Black's two stones inside fill territory; the prisoner counts under territory rules
+        ("prisoners", format!("(;SZ[9]KM[6.5]RU[Japanese]{}AB[ba]AW[aa];B[ab])", WALLS), 16, 27, -16.5),
+    ]
+}
+
+fn main() {
+    let mut failures = 0;
+    for (name, sgf, black, white, lead) in cases() {
+        let game = Game::from_sgf(sgf).expect("case parses");
+        game.jump_to_move_number(game.get_max_move_count());
+        let estimate = game.estimate_territory();
+        let board = game.get_board();
+
+        let mut problems = Vec::new();
+        if (estimate.black_territory, estimate.white_territory) != (black, white) {
+            problems.push(format!("territory {} to {}", estimate.black_territory, estimate.white_territory));
+        }
+        if estimate.score_lead != lead {
+            problems.push(format!("lead {}", estimate.score_lead));
+        }
+        // Stones are owned outright and the values stay in range
+        let width = board.get_width();
+        let wrong = estimate.ownership.iter().enumerate().any(|(i, &value)| {
+            let expected = match board.get_stone(i as u32 % width, i as u32 / width) {
+                Some(StoneColor::Black) => Some(1.0),
+                Some(StoneColor::White) => Some(-1.0),
+                None => None,
+            };
+            !(-1.0..=1.0).contains(&value) || expected.is_some_and(|e| e != value)
+        });
+        if estimate.ownership.len() != (width * board.get_height()) as usize || wrong {
+            problems.push(format!("ownership {:?}", estimate.ownership));
+        }
+
+        if problems.is_empty() {
+            println!("ok    {}", name);
+        } else {
+            failures += 1;
+            println!("FAIL  {:<16} {}", name, problems.join("; "));
+        }
+    }
+
+    // Ownership is laid out row by row: one black stone left of centre on a 7x5 board leans
+    // the left edge Black and leaves the top and bottom rows alike
+    let board = Game::from_sgf("(;SZ[7:5]AB[bc])".to_string()).unwrap().get_board();
+    let ownership = board.estimate_territory(0.0).ownership;
+    let row = |y: usize| ownership[y * 7..(y + 1) * 7].to_vec();
+    if ownership[2 * 7 + 1] != 1.0 || ownership[0] <= ownership[6] || row(0) != row(4) || row(1) != row(3) {
+        failures += 1;
+        println!("FAIL  layout           {:?}", ownership);
+    } else {
+        println!("ok    layout");
+    }
+
+    // Swapping the colors negates the estimate
+    let black = Game::from_sgf("(;SZ[9]AB[cc][gc][dg]AW[ee][fg])".to_string()).unwrap().get_board();
+    let white = Game::from_sgf("(;SZ[9]AW[cc][gc][dg]AB[ee][fg])".to_string()).unwrap().get_board();
+    let (b, w) = (black.estimate_territory(0.0), white.estimate_territory(0.0));
+    let negated: Vec<f64> = w.ownership.iter().map(|v| -v).collect();
+    if b.ownership != negated || b.score_lead != -w.score_lead || b.black_territory != w.white_territory {
+        failures += 1;
+        println!("FAIL  colors swapped   leads {} and {}", b.score_lead, w.score_lead);
+    } else {
+        println!("ok    colors swapped");
+    }
+
+    if failures > 0 {
+        eprintln!("{} case(s) failed", failures);
+        std::process::exit(1);
+    }
+}
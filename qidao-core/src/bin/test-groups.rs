@@ -0,0 +1,117 @@
+//! Sets up small positions and checks the chains, liberties, neighbouring enemy chains,
+//! chains in atari and self-atari moves that `Board` reports.
+//!
+//! Usage: cargo run --bin test-groups
+
+use qidao_core::{Game, Group, Point, StoneColor};
+
+use StoneColor::{Black, White};
+
+fn points(list: &[(u32, u32)]) -> Vec<Point> {
+    list.iter().map(|&(x, y)| Point { x, y }).collect()
+}
+
+/// (name, position, point asked about, stones, liberties, first stone of each enemy chain)
+type Case = (&'static str, &'static str, (u32, u32), Vec<(u32, u32)>, Vec<(u32, u32)>, Vec<(u32, u32)>);
+
+fn cases() -> Vec<Case> {
+    vec![
+        (
+            "two stones",
+            "(;SZ[5]AB[bb][cb]AW[ba][ab])",
+            (2, 1),
+            vec![(1, 1), (2, 1)],
+            vec![(2, 0), (3, 1), (1, 2), (2, 2)],
+            vec![(1, 0), (0, 1)],
+        ),
+        // A liberty shared by two stones of the chain is counted once
+        (
+            "shared liberty",
+            "(;SZ[5]AW[aa][ba][ab]AB[ca][cb][bc][ac])",
+            (0, 0),
+            vec![(0, 0), (1, 0), (0, 1)],
+            vec![(1, 1)],
+            vec![(2, 0), (0, 2)],
+        ),
+        ("corner", "(;SZ[9]AB[aa])", (0, 0), vec![(0, 0)], vec![(1, 0), (0, 1)], vec![]),
+        ("empty point", "(;SZ[5]AB[aa])", (2, 2), vec![], vec![], vec![]),
+    ]
+}
+
+fn main() {
+    let mut failures = 0;
+    for (name, sgf, (x, y), stones, liberties, enemies) in cases() {
+        let board = Game::from_sgf(sgf.to_string()).expect("case parses").get_board();
+        let mut problems = Vec::new();
+
+        let group = board.get_group(x, y);
+        let (found_stones, found_liberties) = match &group {
+            Some(g) => (g.stones.clone(), g.liberties.clone()),
+            None => (vec![], vec![]),
+        };
+        if found_stones != points(&stones) || found_liberties != points(&liberties) {
+            problems.push(format!("group {:?} liberties {:?}", found_stones, found_liberties));
+        }
+        if board.get_liberty_count(x, y) != liberties.len() as u32 {
+            problems.push(format!("{} liberties counted", board.get_liberty_count(x, y)));
+        }
+        let found_enemies: Vec<Point> =
+            board.get_adjacent_enemy_groups(x, y).iter().filter_map(|g| g.stones.first().copied()).collect();
+        if found_enemies != points(&enemies) {
+            problems.push(format!("enemy chains at {:?}", found_enemies));
+        }
+
+        if problems.is_empty() {
+            println!("ok    {}", name);
+        } else {
+            failures += 1;
+            println!("FAIL  {:<16} {}", name, problems.join("; "));
+        }
+    }
+
+    // Two white chains are in atari, the Black stone next to the corner is not
+    let board = Game::from_sgf("(;SZ[5]AW[aa][dd]AB[ba][cd][dc][ed])".to_string()).unwrap().get_board();
+    let first_stones = |groups: Vec<Group>| -> Vec<Point> { groups.iter().map(|g| g.stones[0]).collect() };
+    let in_atari = [
+        first_stones(board.get_groups_in_atari(None)),
+        first_stones(board.get_groups_in_atari(Some(White))),
+        first_stones(board.get_groups_in_atari(Some(Black))),
+    ];
+    let expected = [points(&[(0, 0), (3, 3)]), points(&[(0, 0), (3, 3)]), vec![]];
+    if in_atari != expected {
+        failures += 1;
+        println!("FAIL  atari            {:?}", in_atari);
+    } else {
+        println!("ok    atari");
+    }
+
+    // (position, move, whether it is self-atari)
+    let self_atari = [
+        ("(;SZ[5]AW[ab][bb][cb])", (0, 0, Black), true),
+        ("(;SZ[5]AW[ab][bb][cb])", (2, 2, Black), false),
+        // Capturing the corner stone gives the new stone three liberties
+        ("(;SZ[5]AW[aa]AB[ba][cb][bc])", (0, 1, Black), false),
+        // Extending a chain in atari where only one new liberty is gained keeps it there
+        ("(;SZ[5]AB[aa][ba]AW[ca][bb])", (0, 1, Black), true),
+        // Occupied points are not moves at all
+        ("(;SZ[5]AW[ab][bb][cb])", (0, 1, Black), false),
+    ];
+    let wrong: Vec<String> = self_atari
+        .iter()
+        .filter(|(sgf, (x, y, color), expected)| {
+            Game::from_sgf(sgf.to_string()).unwrap().get_board().is_self_atari(*x, *y, *color) != *expected
+        })
+        .map(|(sgf, (x, y, _), _)| format!("({}, {}) in {}", x, y, sgf))
+        .collect();
+    if wrong.is_empty() {
+        println!("ok    self-atari");
+    } else {
+        failures += 1;
+        println!("FAIL  self-atari       {}", wrong.join("; "));
+    }
+
+    if failures > 0 {
+        eprintln!("{} case(s) failed", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,106 @@
+//! Checks the fixed handicap points, and what fixed and free placement write to the root:
+//! `HA`, `AB`, komi by rule set and White to move.
+//!
+//! Usage: cargo run --bin test-handicap
+
+use qidao_core::{fixed_handicap_points, format_move, Game, Move, Notation, Point, RuleSet, StoneColor};
+
+/// (board size, stones, GTP vertices in placement order)
+const FIXED: [(u32, u32, &str); 8] = [
+    (19, 2, "D4 Q16"),
+    (19, 3, "D4 Q16 D16"),
+    (19, 5, "D4 Q16 D16 Q4 K10"),
+    (19, 8, "D4 Q16 D16 Q4 D10 Q10 K4 K16"),
+    (19, 9, "D4 Q16 D16 Q4 D10 Q10 K4 K16 K10"),
+    (13, 4, "D4 K10 D10 K4"),
+    (9, 6, "C3 G7 C7 G3 C5 G5"),
+    (10, 4, "C3 H8 C8 H3"),
+];
+
+fn vertices(points: &[Point], size: u32) -> String {
+    let vertex = |&point| format_move(Move::Play { point }, Notation::Gtp, size, size).unwrap();
+    points.iter().map(vertex).collect::<Vec<_>>().join(" ")
+}
+
+/// The value of `id` in the root of `game`, as saved.
+fn root_value(game: &Game, id: &str) -> Option<Vec<String>> {
+    let root = game.get_root_node().get_properties();
+    root.into_iter().find(|p| p.identifier == id).map(|p| p.values)
+}
+
+fn main() {
+    let mut failures = Vec::new();
+    for (size, count, expected) in FIXED {
+        match fixed_handicap_points(size, count) {
+            Ok(points) if vertices(&points, size) == expected => {}
+            Ok(points) => failures.push(format!("{} on {}x{}: {}", count, size, size, vertices(&points, size))),
+            Err(e) => failures.push(format!("{} on {}x{}: {}", count, size, size, e)),
+        }
+    }
+    for (size, count) in [(19, 1), (19, 10), (10, 5), (5, 2)] {
+        if fixed_handicap_points(size, count).is_ok() {
+            failures.push(format!("{} stones accepted on {}x{}", count, size, size));
+        }
+    }
+
+    // Fixed placement on a new game: half a point of komi under the default Chinese rules
+    let game = Game::with_handicap(19, 4).expect("handicap game");
+    let stones = game.get_current_board_stones().len();
+    let km = root_value(&game, "KM");
+    if root_value(&game, "HA") != Some(vec!["4".into()]) || stones != 4 || km != Some(vec!["0.5".into()]) {
+        failures.push(format!("with_handicap: {} stones, KM {:?}", stones, km));
+    }
+    if game.get_next_color() != StoneColor::White || game.get_initial_player() != StoneColor::White {
+        failures.push("Black moves first in a handicap game".into());
+    }
+    // Setting it again replaces the stones
+    game.set_fixed_handicap(2).unwrap();
+    if game.get_current_board_stones().len() != 2 || root_value(&game, "HA") != Some(vec!["2".into()]) {
+        failures.push("a second handicap kept the first stones".into());
+    }
+
+    // Area rules without a handicap bonus play without komi; a recorded KM is kept
+    let nz = Game::new(9).unwrap();
+    nz.set_rules(RuleSet::NewZealand.rules());
+    nz.set_fixed_handicap(2).unwrap();
+    if root_value(&nz, "KM") != Some(vec!["0".into()]) {
+        failures.push(format!("New Zealand komi {:?}", root_value(&nz, "KM")));
+    }
+    let recorded = Game::from_sgf("(;SZ[19]KM[6.5])".into()).unwrap();
+    recorded.set_fixed_handicap(3).unwrap();
+    if root_value(&recorded, "KM") != Some(vec!["6.5".into()]) {
+        failures.push(format!("recorded komi became {:?}", root_value(&recorded, "KM")));
+    }
+
+    // Free placement takes any distinct points on the board, before the first move
+    let free = Game::new_rectangular(13, 19).unwrap();
+    let corners = vec![Point { x: 0, y: 0 }, Point { x: 12, y: 18 }, Point { x: 6, y: 9 }];
+    free.set_free_handicap(corners).unwrap();
+    if root_value(&free, "AB") != Some(vec!["aa".into(), "ms".into(), "gj".into()]) {
+        failures.push(format!("free placement AB {:?}", root_value(&free, "AB")));
+    }
+    for (name, stones) in [
+        ("one stone", vec![Point { x: 3, y: 3 }]),
+        ("duplicate", vec![Point { x: 3, y: 3 }, Point { x: 3, y: 3 }]),
+        ("off the board", vec![Point { x: 3, y: 3 }, Point { x: 13, y: 3 }]),
+    ] {
+        if free.set_free_handicap(stones).is_ok() {
+            failures.push(format!("free placement accepted {}", name));
+        }
+    }
+    if free.set_fixed_handicap(2).is_ok() {
+        failures.push("fixed handicap accepted on 13x19".into());
+    }
+    free.place_stone(3, 3, StoneColor::White).unwrap();
+    free.jump_to_move_number(0);
+    if free.set_free_handicap(vec![Point { x: 2, y: 2 }, Point { x: 9, y: 9 }]).is_ok() {
+        failures.push("handicap accepted after the first move".into());
+    }
+
+    if failures.is_empty() {
+        println!("ok    {} fixed placements, free placement", FIXED.len());
+    } else {
+        eprintln!("FAIL  {}", failures.join("; "));
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,146 @@
+//! Imports the Tygem, WBaduk, PandaNet and KGS fixtures and checks the game information and
+//! moves, then the results read from free text and what `.qgs` files may carry.
+//!
+//! Usage: cargo run --bin test-kifu [fixtures-dir]
+//! Defaults to `assets/kifu` next to the crate.
+
+use qidao_core::{kifu_format_for_extension, Game, KifuFormat};
+
+struct Fixture {
+    file: &'static str,
+    /// Black name and rank, then White name and rank
+    players: [&'static str; 4],
+    komi: f64,
+    result: &'static str,
+    date: &'static str,
+    /// Start of the main line as written to SGF
+    moves: &'static str,
+}
+
+const FIXTURES: [Fixture; 4] = [
+    Fixture {
+        file: "tygem.gib",
+        players: ["이세돌", "9d", "박정환", "9d"],
+        komi: 6.5,
+        result: "B+R",
+        date: "2016-07-17",
+        moves: ";B[pd];W[dp];B[qp];W[cd];B[]",
+    },
+    Fixture {
+        file: "wbaduk.ngf",
+        players: ["BlackPlayer", "2d", "WhitePlayer", "3d"],
+        komi: 6.5,
+        result: "W+R",
+        date: "2004-05-19",
+        moves: ";B[pd];W[dp];B[qp];W[cd]",
+    },
+    Fixture {
+        file: "pandanet.ugf",
+        players: ["Black Player", "5d", "White Player", "7d"],
+        komi: 0.5,
+        result: "W+3.5",
+        date: "2002-02-23",
+        moves: "HA[2]AB[dp][pd];W[qd];B[qq];W[dd]",
+    },
+    Fixture {
+        file: "kgs.qgs",
+        players: ["BlackPlayer", "3d", "WhitePlayer", "4d"],
+        komi: 6.5,
+        result: "W+T",
+        date: "2008-11-02",
+        moves: ";B[pd]BL[1795.2];W[dd]WL[1793.4]",
+    },
+];
+
+/// (result line of a WBaduk record, RE it becomes)
+const RESULTS: [(&str, &str); 6] = [
+    ("White wins by resignation!", "W+R"),
+    ("Black wins by 3.5", "B+3.5"),
+    ("백 시간승", "W+T"),
+    ("B+R", "B+R"),
+    ("W+T", "W+T"),
+    ("w+r", "W+R"),
+];
+
+/// (name, `.qgs` content, black player it opens with, or `None` if it must be refused)
+const QGS: [(&str, &str, Option<&str>); 3] = [
+    ("qgs lead-in", "Exported record\n\n(;SZ[19]PB[Shin Jinseo];B[pd])", Some("Shin Jinseo")),
+    (
+        "qgs with ugf",
+        "[Header]\nPlayerB=Cho Hunhyun,9p\nPlayerW=Lee Changho,9p\n[Data]\nQD,B1,0\n",
+        Some("Cho Hunhyun"),
+    ),
+    ("qgs without a game", "Exported record\nno moves here\n", None),
+];
+
+fn main() {
+    let dir = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| format!("{}/../assets/kifu", env!("CARGO_MANIFEST_DIR")));
+
+    let mut failures = 0;
+    for Fixture { file: name, players, komi, result, date, moves } in FIXTURES {
+        let path = format!("{}/{}", dir, name);
+        let bytes = std::fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+        let format = kifu_format_for_extension(name.rsplit('.').next().unwrap_or_default().to_string());
+
+        let mut problems = Vec::new();
+        // Both the extension and detection from the text have to work
+        for format in [format, None] {
+            let game = match Game::from_kifu_bytes(bytes.clone(), format) {
+                Ok(game) => game,
+                Err(e) => {
+                    problems.push(format!("{:?} does not load: {:?}", format, e));
+                    continue;
+                }
+            };
+            let meta = game.get_metadata();
+            let found = [meta.black_name, meta.black_rank, meta.white_name, meta.white_rank];
+            if found != players {
+                problems.push(format!("players {:?}", found));
+            }
+            if meta.komi != komi || meta.result != result || meta.date != date {
+                problems.push(format!("komi {} result {} date {}", meta.komi, meta.result, meta.date));
+            }
+            if !game.to_sgf().contains(moves) {
+                problems.push(format!("moves differ: {}", game.to_sgf()));
+            }
+        }
+
+        if problems.is_empty() {
+            println!("ok    {:<14} {} vs {}, {}", name, players[0], players[2], result);
+        } else {
+            failures += 1;
+            println!("FAIL  {:<14} {}", name, problems.join("; "));
+        }
+    }
+
+    // Results written out in words or already in SGF form
+    let ngf = std::fs::read_to_string(format!("{}/wbaduk.ngf", dir)).expect("wbaduk.ngf");
+    for (text, expected) in RESULTS {
+        let record = ngf.replace("White wins by resignation!", text);
+        let result = Game::from_kifu(record, Some(KifuFormat::Ngf)).map(|g| g.get_metadata().result);
+        if result.as_deref().ok() != Some(expected) {
+            failures += 1;
+            println!("FAIL  {:<14} read as {:?}", text, result);
+        } else {
+            println!("ok    {:<14} {}", text, expected);
+        }
+    }
+
+    for (name, content, black) in QGS {
+        let game = Game::from_kifu(content.to_string(), Some(KifuFormat::Qgs));
+        let found = game.as_ref().ok().map(|g| g.get_metadata().black_name);
+        if found.as_deref() != black {
+            failures += 1;
+            println!("FAIL  {:<14} opened as {:?}", name, game.map(|g| g.get_metadata().black_name));
+        } else {
+            println!("ok    {}", name);
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{} case(s) failed", failures);
+        std::process::exit(1);
+    }
+}
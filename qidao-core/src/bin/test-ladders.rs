@@ -0,0 +1,91 @@
+//! Reads ladders on a 9x9 board, with and without breakers, and checks the outcome, the
+//! breakers found and that the main line can be replayed move by move.
+//!
+//! Usage: cargo run --bin test-ladders
+
+use std::sync::Arc;
+
+use qidao_core::{Board, Game, LadderResult, Point};
+
+/// (name, position, chased stone, whether the ladder works, breakers)
+type Case = (&'static str, &'static str, (u32, u32), bool, Vec<(u32, u32)>);
+
+fn cases() -> Vec<Case> {
+    vec![
+        // White is in atari and runs towards the lower right corner
+        ("in atari", "(;SZ[9]AW[dd]AB[cd][dc][ec][de])", (3, 3), true, vec![]),
+        ("two liberties", "(;SZ[9]AW[dd]AB[cd][dc][ec])", (3, 3), true, vec![]),
+        ("breaker", "(;SZ[9]AW[dd][gg]AB[cd][dc][ec][de])", (3, 3), false, vec![(6, 6)]),
+        ("far breaker", "(;SZ[9]AW[dd][hh]AB[cd][dc][ec][de])", (3, 3), false, vec![(7, 7)]),
+        // A black stone in front of the breaker stops the chase earlier
+        ("blocked", "(;SZ[9]AW[dd][gg]AB[cd][dc][ec][de][ff])", (3, 3), true, vec![]),
+        // Taking the stone in atari next to it gets White out
+        ("capture", "(;SZ[9]AW[dd][ce][df]AB[cd][dc][ec][de])", (3, 3), false, vec![]),
+    ]
+}
+
+/// Replays the main line; returns the problem found, if any.
+fn replay(board: &Arc<Board>, (x, y): (u32, u32), result: &LadderResult) -> Option<String> {
+    let mut board = board.clone();
+    for (i, mv) in result.sequence.iter().enumerate() {
+        if i > 0 && result.sequence[i - 1].color == mv.color {
+            return Some(format!("move {} repeats {:?}", i + 1, mv.color));
+        }
+        match board.place_stone(mv.point.x, mv.point.y, mv.color) {
+            Ok(next) => board = next,
+            Err(e) => return Some(format!("move {} at {:?}: {}", i + 1, mv.point, e)),
+        }
+    }
+    // A working ladder ends with the chain taken, or in atari with nowhere to go
+    let liberties = board.get_liberty_count(x, y);
+    if result.captured && liberties > 1 {
+        return Some(format!("the line ends with {} liberties", liberties));
+    }
+    if !result.captured && liberties < 2 {
+        return Some("the escape ends in atari".to_string());
+    }
+    None
+}
+
+fn main() {
+    let mut failures = 0;
+    for (name, sgf, (x, y), captured, breakers) in cases() {
+        let board = Game::from_sgf(sgf.to_string()).expect("case parses").get_board();
+        let mut problems = Vec::new();
+        match board.read_ladder(x, y) {
+            Some(result) => {
+                if result.captured != captured {
+                    problems.push(format!("captured: {}", result.captured));
+                }
+                let expected: Vec<Point> = breakers.iter().map(|&(x, y)| Point { x, y }).collect();
+                if result.breakers != expected {
+                    problems.push(format!("breakers {:?}", result.breakers));
+                }
+                problems.extend(replay(&board, (x, y), &result));
+            }
+            None => problems.push("not read".to_string()),
+        }
+
+        if problems.is_empty() {
+            println!("ok    {}", name);
+        } else {
+            failures += 1;
+            println!("FAIL  {:<16} {}", name, problems.join("; "));
+        }
+    }
+
+    // Only chains with one or two liberties are read
+    for (name, sgf) in [("empty point", "(;SZ[9]AB[cd])"), ("three liberties", "(;SZ[9]AW[dd]AB[dc])")] {
+        if Game::from_sgf(sgf.to_string()).unwrap().get_board().read_ladder(3, 3).is_some() {
+            failures += 1;
+            println!("FAIL  {:<16} read as a ladder", name);
+        } else {
+            println!("ok    {}", name);
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{} case(s) failed", failures);
+        std::process::exit(1);
+    }
+}
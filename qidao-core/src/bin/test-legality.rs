@@ -0,0 +1,89 @@
+//! Checks the reason `check_move` gives on `Board` and `Game` for each kind of illegal
+//! move, that checking leaves the position alone, and the legal move lists.
+//!
+//! Usage: cargo run --bin test-legality
+
+use qidao_core::{Game, Legality, Point, StoneColor};
+
+use StoneColor::{Black, White};
+
+/// (name, game, move at the last node, reason from `Board`, reason from `Game`)
+type Case = (&'static str, &'static str, (u32, u32, StoneColor), Legality, Legality);
+
+fn cases() -> Vec<Case> {
+    vec![
+        ("legal", "(;SZ[5];B[cc])", (1, 1, White), Legality::Legal, Legality::Legal),
+        ("off the board", "(;SZ[5])", (5, 0, Black), Legality::OutOfBounds, Legality::OutOfBounds),
+        ("rectangle", "(;SZ[5:3])", (0, 3, Black), Legality::OutOfBounds, Legality::OutOfBounds),
+        ("occupied", "(;SZ[5];B[cc])", (2, 2, White), Legality::Occupied, Legality::Occupied),
+        ("suicide", "(;SZ[5]AB[ba][ab])", (0, 0, White), Legality::Suicide, Legality::Suicide),
+        // New Zealand rules allow suicide of a chain but not of a single stone
+        ("one-stone suicide", "(;SZ[5]RU[NZ]AB[ba][ab])", (0, 0, White), Legality::Suicide, Legality::Suicide),
+        ("chain suicide", "(;SZ[5]RU[NZ]AB[ca][bb][ab]AW[aa])", (1, 0, White), Legality::Legal, Legality::Legal),
+        // The point has no empty neighbours, but taking the corner stone gives it one
+        ("capture, not suicide", "(;SZ[5]AB[aa][bb][ac]AW[ba])", (0, 1, White), Legality::Legal, Legality::Legal),
+        ("ko", "(;SZ[5]AB[ba][ab][bc]AW[ca][db][cc][bb];B[cb])", (1, 1, White), Legality::Ko, Legality::Ko),
+        (
+            "ko after a threat",
+            "(;SZ[5]AB[ba][ab][bc]AW[ca][db][cc][bb];B[cb];W[ee];B[ed])",
+            (1, 1, White),
+            Legality::Legal,
+            Legality::Legal,
+        ),
+        // Only the game knows the position after move 2 came before
+        ("superko", "(;SZ[3:1]RU[Chinese];B[aa];W[ca];B[ba];W[ca])", (0, 0, Black), Legality::Legal, Legality::Superko),
+        ("no superko", "(;SZ[3:1]RU[Japanese];B[aa];W[ca];B[ba];W[ca])", (0, 0, Black), Legality::Legal, Legality::Legal),
+    ]
+}
+
+fn main() {
+    let mut failures = 0;
+    for (name, sgf, (x, y, color), board_reason, game_reason) in cases() {
+        let game = Game::from_sgf(sgf.to_string()).expect("case parses");
+        game.jump_to_move_number(game.get_max_move_count());
+        let board = game.get_board();
+        let (moves, hash) = (game.get_move_count(), board.get_hash());
+
+        let mut problems = Vec::new();
+        if board.check_move(x, y, color) != board_reason {
+            problems.push(format!("board says {:?}", board.check_move(x, y, color)));
+        }
+        if game.check_move(x, y, color) != game_reason {
+            problems.push(format!("game says {:?}", game.check_move(x, y, color)));
+        }
+        if game.get_move_count() != moves || game.get_board().get_hash() != hash {
+            problems.push("checking changed the position".to_string());
+        }
+        // Legal moves are exactly the empty points the game accepts
+        let listed = game.get_legal_moves(color).contains(&Point { x, y });
+        if listed != (game_reason == Legality::Legal) {
+            problems.push(format!("{}listed as a legal move", if listed { "" } else { "not " }));
+        }
+        let played = game.place_stone(x, y, color);
+        if played.is_ok() != (game_reason == Legality::Legal) {
+            problems.push(format!("place_stone gave {:?}", played));
+        }
+
+        if problems.is_empty() {
+            println!("ok    {}", name);
+        } else {
+            failures += 1;
+            println!("FAIL  {:<20} {}", name, problems.join("; "));
+        }
+    }
+
+    // White may play anywhere empty on a 3x3 board but the corner Black has surrounded
+    let board = Game::from_sgf("(;SZ[3]AB[ba][ab])".to_string()).unwrap().get_board();
+    let (white, black) = (board.get_legal_moves(White), board.get_legal_moves(Black));
+    if white.len() != 6 || white.contains(&Point { x: 0, y: 0 }) || black.len() != 7 {
+        failures += 1;
+        println!("FAIL  legal moves          White {:?}, Black {:?}", white, black);
+    } else {
+        println!("ok    legal moves");
+    }
+
+    if failures > 0 {
+        eprintln!("{} case(s) failed", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,76 @@
+//! Classifies the chains of small positions as unconditionally alive, seki or undecided and
+//! checks the seki points.
+//!
+//! Usage: cargo run --bin test-life
+
+use qidao_core::{ChainStatus, Game, Point};
+
+use ChainStatus::{Seki, UnconditionallyAlive, Undecided};
+
+/// (name, position, status of the chain at each point, seki points)
+type Case = (&'static str, &'static str, Vec<((u32, u32), ChainStatus)>, Vec<(u32, u32)>);
+
+fn cases() -> Vec<Case> {
+    vec![
+        // Three one-point eyes along the top edge; the white stone below can still be taken
+        (
+            "eyes",
+            "(;SZ[5]AB[ba][da][ab][bb][cb][db][eb]AW[cd])",
+            vec![((1, 1), UnconditionallyAlive), ((3, 0), UnconditionallyAlive), ((2, 3), Undecided)],
+            vec![],
+        ),
+        // A two-point eye counts as one region, so one more eye is enough
+        ("big eye", "(;SZ[5]AB[ba][ab][bb][cb][db][eb])", vec![((1, 1), UnconditionallyAlive)], vec![]),
+        ("one eye", "(;SZ[5]AB[ba][ca][da][ea][ab][bb][cb][db][eb])", vec![((1, 1), Undecided)], vec![]),
+        // Neither side can fill one of the two shared liberties without being taken
+        ("seki", "(;SZ[3:2]AB[aa][ab]AW[ca][cb])", vec![((0, 0), Seki), ((2, 1), Seki)], vec![(1, 0), (1, 1)]),
+        // With a third liberty White connects safely, so nothing is settled
+        ("no seki", "(;SZ[3]AB[aa][ab][ac]AW[ca][cb][cc])", vec![((0, 0), Undecided), ((2, 2), Undecided)], vec![]),
+        ("empty", "(;SZ[5])", vec![], vec![]),
+    ]
+}
+
+fn main() {
+    let mut failures = 0;
+    for (name, sgf, statuses, seki) in cases() {
+        let board = Game::from_sgf(sgf.to_string()).expect("case parses").get_board();
+        let mut problems = Vec::new();
+        for ((x, y), status) in statuses {
+            if board.get_chain_status(x, y) != Some(status) {
+                problems.push(format!("({}, {}) is {:?}", x, y, board.get_chain_status(x, y)));
+            }
+        }
+        // Every chain is listed once, agreeing with the status of its stones
+        let all = board.analyze_life();
+        let stones: usize = all.iter().map(|c| c.group.stones.len()).sum();
+        let listed = all.iter().all(|c| {
+            c.group.stones.iter().all(|p| board.get_chain_status(p.x, p.y) == Some(c.status))
+        });
+        let counted = (0..board.get_height())
+            .flat_map(|y| (0..board.get_width()).map(move |x| (x, y)))
+            .filter(|&(x, y)| board.get_stone(x, y).is_some())
+            .count();
+        if !listed || stones != counted {
+            problems.push(format!("{} chains listing {} of {} stones", all.len(), stones, counted));
+        }
+        let expected: Vec<Point> = seki.iter().map(|&(x, y)| Point { x, y }).collect();
+        if board.get_seki_points() != expected {
+            problems.push(format!("seki points {:?}", board.get_seki_points()));
+        }
+        if board.get_chain_status(2, 2).is_some() != board.get_stone(2, 2).is_some() {
+            problems.push("an empty point has a status".to_string());
+        }
+
+        if problems.is_empty() {
+            println!("ok    {}", name);
+        } else {
+            failures += 1;
+            println!("FAIL  {:<16} {}", name, problems.join("; "));
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{} case(s) failed", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,126 @@
+//! Edits markup on the nodes of a small game and checks what the node reports, what is
+//! saved and what comes back after reloading: shapes, labels, arrows, lines and dimming.
+//!
+//! Usage: cargo run --bin test-markup
+
+use qidao_core::{Game, Label, LabelStyle, MarkupShape, NodeMarkup, Point, Segment};
+
+fn p(x: u32, y: u32) -> Point {
+    Point { x, y }
+}
+
+/// Markup of the node at `moves` after saving and reloading `game`.
+fn reloaded(game: &Game, moves: u32) -> NodeMarkup {
+    let copy = Game::from_sgf(game.to_sgf()).expect("saved game parses");
+    copy.jump_to_move_number(moves);
+    copy.get_markup()
+}
+
+fn main() {
+    let mut failures = Vec::new();
+    let game = Game::from_sgf("(;SZ[9]DD[aa][ba];B[cc];W[gg])".to_string()).unwrap();
+    game.jump_to_move_number(1);
+
+    // One shape per point: a square replaces the triangle, toggling it again clears the point
+    let steps = [
+        game.toggle_markup(2, 2, MarkupShape::Triangle),
+        game.toggle_markup(2, 2, MarkupShape::Square),
+        game.toggle_markup(4, 4, MarkupShape::Circle),
+        game.toggle_markup(4, 4, MarkupShape::Circle),
+        game.toggle_markup(5, 5, MarkupShape::Cross),
+    ];
+    let marks: Vec<(Point, MarkupShape)> = game.get_markup().marks.iter().map(|m| (m.point, m.shape)).collect();
+    if steps.iter().map(|s| *s.as_ref().unwrap()).collect::<Vec<_>>() != [true, true, true, false, true]
+        || marks != [(p(2, 2), MarkupShape::Square), (p(5, 5), MarkupShape::Cross)]
+    {
+        failures.push(format!("shapes {:?}", marks));
+    }
+
+    // Labels take the first free text; numbers and letters are counted separately
+    let texts = [
+        game.toggle_label(0, 8, LabelStyle::Letters),
+        game.toggle_label(1, 8, LabelStyle::Letters),
+        game.toggle_label(0, 8, LabelStyle::Letters),
+        game.toggle_label(2, 8, LabelStyle::Letters),
+        game.toggle_label(3, 8, LabelStyle::Numbers),
+    ];
+    let texts: Vec<Option<String>> = texts.into_iter().map(Result::unwrap).collect();
+    let expected = [Some("A"), Some("B"), None, Some("A"), Some("1")].map(|t| t.map(String::from));
+    if texts != expected {
+        failures.push(format!("label texts {:?}", texts));
+    }
+    game.set_label(4, 8, "x:y]".into()).unwrap();
+
+    // Arrows keep their direction, lines do not
+    let segments = [
+        game.toggle_arrow(p(0, 0), p(2, 2)),
+        game.toggle_arrow(p(2, 2), p(0, 0)),
+        game.toggle_arrow(p(2, 2), p(0, 0)),
+        game.toggle_line(p(6, 0), p(8, 2)),
+        game.toggle_line(p(8, 2), p(6, 0)),
+        game.toggle_line(p(0, 6), p(2, 6)),
+    ];
+    if segments.iter().map(|s| *s.as_ref().unwrap()).collect::<Vec<_>>() != [true, true, false, true, false, true] {
+        failures.push(format!("segments {:?}", segments));
+    }
+
+    // The root's DD carries over; undimming a point here writes this node's own list
+    if game.get_markup().dimmed != [p(0, 0), p(1, 0)] {
+        failures.push(format!("inherited dimming {:?}", game.get_markup().dimmed));
+    }
+    game.toggle_markup(0, 0, MarkupShape::Dimmed).unwrap();
+
+    let markup = game.get_markup();
+    let labels = vec![
+        Label { point: p(1, 8), text: "B".into() },
+        Label { point: p(2, 8), text: "A".into() },
+        Label { point: p(3, 8), text: "1".into() },
+        Label { point: p(4, 8), text: "x:y]".into() },
+    ];
+    if markup.labels != labels
+        || markup.arrows != [Segment { from: p(0, 0), to: p(2, 2) }]
+        || markup.lines != [Segment { from: p(0, 6), to: p(2, 6) }]
+        || markup.dimmed != [p(1, 0)]
+    {
+        failures.push(format!("markup {:?}", markup));
+    }
+    let sgf = game.to_sgf();
+    for written in [r"LB[bi:B][ci:A][di:1][ei:x\:y\]]", "SQ[cc]", "MA[ff]", "AR[aa:cc]", "LN[ag:cg]", "DD[ba]"] {
+        if !sgf.contains(written) {
+            failures.push(format!("{} missing from {}", written, sgf));
+        }
+    }
+    if reloaded(&game, 1) != markup {
+        failures.push(format!("reloaded as {:?}", reloaded(&game, 1)));
+    }
+    // The next node inherits the nearer DD
+    if reloaded(&game, 2).dimmed != [p(1, 0)] {
+        failures.push(format!("dimming after move 1 {:?}", reloaded(&game, 2).dimmed));
+    }
+
+    // Clearing drops the node's own markup and DD; the root's dimming shows again
+    game.clear_markup();
+    let cleared = game.get_markup();
+    if !cleared.marks.is_empty() || !cleared.labels.is_empty() || cleared.dimmed != [p(0, 0), p(1, 0)] {
+        failures.push(format!("after clearing {:?}", cleared));
+    }
+
+    // Letters go on past Z
+    let letters = Game::new(9).unwrap();
+    let labels: Vec<_> = (0..27).map(|i| letters.toggle_label(i % 9, i / 9, LabelStyle::Letters).unwrap()).collect();
+    let last = labels.last().cloned().flatten();
+    if last.as_deref() != Some("AA") {
+        failures.push(format!("27th label {:?}", last));
+    }
+
+    if game.toggle_markup(9, 0, MarkupShape::Circle).is_ok() || game.set_label(0, 9, "A".into()).is_ok() {
+        failures.push("markup accepted off the board".into());
+    }
+
+    if failures.is_empty() {
+        println!("ok    shapes, labels, arrows, lines and dimming");
+    } else {
+        eprintln!("FAIL  {}", failures.join("; "));
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,84 @@
+//! Records and loads passes and checks the pass streak, the end of the game, the side to
+//! move and the moves sent to the engine.
+//!
+//! Usage: cargo run --bin test-passes
+
+use qidao_core::{Game, StoneColor};
+
+use StoneColor::{Black, White};
+
+/// (name, game, passes in a row at the end, engine moves)
+type Case = (&'static str, &'static str, u32, Vec<[&'static str; 2]>);
+
+fn cases() -> Vec<Case> {
+    vec![
+        ("empty values", "(;SZ[19];B[pd];W[];B[])", 2, vec![["B", "Q16"], ["W", "pass"], ["B", "pass"]]),
+        // FF[3] files write passes as tt on boards up to 19x19
+        ("tt", "(;FF[3]SZ[19];B[pd];W[tt];B[tt])", 2, vec![["B", "Q16"], ["W", "pass"], ["B", "pass"]]),
+        ("tt on 21x21", "(;SZ[21];B[tt])", 0, vec![["B", "U2"]]),
+        ("comment between", "(;SZ[9];B[];C[Both pass?];W[])", 2, vec![["B", "pass"], ["W", "pass"]]),
+        ("move after passes", "(;SZ[9];B[];W[];B[ee])", 0, vec![["B", "pass"], ["W", "pass"], ["B", "E5"]]),
+    ]
+}
+
+fn main() {
+    let mut failures = 0;
+    for (name, sgf, passes, moves) in cases() {
+        let game = Game::from_sgf(sgf.to_string()).expect("case parses");
+        game.jump_to_move_number(game.get_max_move_count());
+
+        let mut problems = Vec::new();
+        if game.get_consecutive_passes() != passes || game.is_game_over() != (passes >= 2) {
+            problems.push(format!("{} passes in a row", game.get_consecutive_passes()));
+        }
+        let sent = game.get_analysis_moves().unwrap_or_default();
+        if sent != moves.iter().map(|m| m.map(String::from).to_vec()).collect::<Vec<_>>() {
+            problems.push(format!("engine moves {:?}", sent));
+        }
+        let stones = game.get_current_board_stones().len();
+        let played = moves.iter().filter(|[_, v]| *v != "pass").count();
+        if stones != played {
+            problems.push(format!("{} stones on the board", stones));
+        }
+
+        if problems.is_empty() {
+            println!("ok    {}", name);
+        } else {
+            failures += 1;
+            println!("FAIL  {:<20} {}", name, problems.join("; "));
+        }
+    }
+
+    // Recording: passes are written as empty values and hand the turn over; passing again
+    // from the same node follows the recorded pass
+    let game = Game::new(9).unwrap();
+    let mut problems = Vec::new();
+    game.place_stone(4, 4, Black).unwrap();
+    game.pass(White);
+    if game.get_next_color() != Black || game.is_game_over() {
+        problems.push("one pass".to_string());
+    }
+    game.pass(Black);
+    if game.get_next_color() != White || !game.is_game_over() {
+        problems.push("two passes".to_string());
+    }
+    game.go_back();
+    game.pass(Black);
+    if game.get_variation_count() != 1 {
+        problems.push("passing again added a branch".to_string());
+    }
+    if !game.to_sgf().contains(";B[ee];W[];B[])") {
+        problems.push(format!("saved as {}", game.to_sgf()));
+    }
+    if problems.is_empty() {
+        println!("ok    recording");
+    } else {
+        failures += 1;
+        println!("FAIL  recording            {}", problems.join("; "));
+    }
+
+    if failures > 0 {
+        eprintln!("{} case(s) failed", failures);
+        std::process::exit(1);
+    }
+}
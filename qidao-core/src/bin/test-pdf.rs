@@ -0,0 +1,158 @@
+//! Lays out asset games as PDF kifu booklets and checks the page count and header, and
+//! the figures and captions of a game with side branches and comments.
+//! Writes the booklets to a temporary directory for a look.
+//!
+//! Usage: cargo run --bin test-pdf [game.sgf]
+
+use qidao_core::{default_kifu_options, Game, KifuFigure, KifuOptions, PageSize};
+
+/// Pages in the document, counted from the page objects pdf-writer writes.
+fn page_count(pdf: &[u8]) -> usize {
+    let text = String::from_utf8_lossy(pdf);
+    text.matches("/Type /Page\n").count() + text.matches("/Type /Page ").count()
+}
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| format!("{}/../assets/games/test.sgf", env!("CARGO_MANIFEST_DIR")));
+    let game = Game::from_sgf(std::fs::read_to_string(&path).expect("game file")).expect("game parses");
+    let out = std::env::temp_dir();
+    let mut failures = Vec::new();
+
+    game.jump_to_move_number(game.get_max_move_count().min(10));
+    let start = game.get_current_node().get_id();
+    let options = KifuOptions { moves_per_diagram: 20, ..default_kifu_options() };
+    let pdf = game.to_pdf(options.clone()).expect("pdf renders");
+    std::fs::write(out.join("qidao-kifu.pdf"), &pdf).unwrap();
+    if game.get_current_node().get_id() != start {
+        failures.push("to_pdf moved the current node".to_string());
+    }
+    if !pdf.starts_with(b"%PDF-") || !pdf.ends_with(b"%%EOF") && !pdf.ends_with(b"%%EOF\n") {
+        failures.push("output is not a PDF document".to_string());
+    }
+
+    // Without variations the main line alone decides the pages; every node after the root
+    // of the asset games plays a move
+    let main_line = KifuOptions { variations: false, ..options.clone() };
+    let mut moves = 0;
+    let mut node = game.get_root_node();
+    while let Some(next) = node.get_children().first().cloned() {
+        moves += 1;
+        node = next;
+    }
+    let diagrams = (moves as usize).max(1).div_ceil(20);
+    let expected = diagrams.div_ceil(2);
+    let pages = page_count(&game.to_pdf(main_line.clone()).unwrap());
+    if pages != expected {
+        failures.push(format!("{} pages for {} diagrams, expected {}", pages, diagrams, expected));
+    }
+    let single = KifuOptions { diagrams_per_page: 1, page_size: PageSize::Letter, restart_numbering: true, ..main_line };
+    let pages = page_count(&game.to_pdf(single).unwrap());
+    if pages != diagrams {
+        failures.push(format!("{} pages at one diagram per page, expected {}", pages, diagrams));
+    }
+
+    let empty = Game::from_sgf("(;SZ[9]PB[Black]PW[White])".to_string()).unwrap();
+    if page_count(&empty.to_pdf(default_kifu_options()).unwrap()) != 1 {
+        failures.push("a game without moves does not make one page".to_string());
+    }
+
+    failures.extend(check_variations());
+    failures.extend(check_move_numbers());
+
+    if failures.is_empty() {
+        println!("ok    {} bytes, {} diagrams in {}", pdf.len(), diagrams, out.display());
+    } else {
+        eprintln!("FAIL  {}", failures.join("; "));
+        std::process::exit(1);
+    }
+}
+
+/// Five-move diagrams of `variations.sgf`: the main line in three diagrams, a variation
+/// after each of the first two, and no figure for the branch that plays no move.
+fn check_variations() -> Vec<String> {
+    let path = format!("{}/../assets/games/variations.sgf", env!("CARGO_MANIFEST_DIR"));
+    let game = Game::from_sgf(std::fs::read_to_string(&path).expect("game file")).expect("game parses");
+    let options = KifuOptions { moves_per_diagram: 5, ..default_kifu_options() };
+    let mut failures = Vec::new();
+
+    let figure = |figures: &[KifuFigure], caption: &str| figures.iter().find(|f| f.caption == caption).cloned();
+    let figures = game.kifu_figures(options.clone());
+    let captions: Vec<&str> = figures.iter().map(|f| f.caption.as_str()).collect();
+    let expected = [
+        "Diagram 1 (1\u{2013}5)",
+        "Variation 1 (1 = move 3)",
+        "Diagram 2 (6\u{2013}10)",
+        "Variation 2 (1 = move 10)",
+        "Diagram 3 (11\u{2013}13)",
+    ];
+    if captions != expected {
+        failures.push(format!("figures {:?}", captions));
+    }
+    let comments = |caption: &str| figure(&figures, caption).map(|f| f.comments).unwrap_or_default();
+    let first = ["A short game with side branches.", "1: Black takes the corner.", "4: White answers."];
+    if comments(expected[0]) != first {
+        failures.push(format!("diagram 1 comments {:?}", comments(expected[0])));
+    }
+    if comments(expected[1]) != ["2: Mirror play."] || comments(expected[3]) != ["2: Black cuts."] {
+        failures.push("variation comments are not numbered from 1".to_string());
+    }
+    if comments(expected[2]) != ["8: A fight starts."] {
+        failures.push(format!("diagram 2 comments {:?}", comments(expected[2])));
+    }
+
+    // Restarted numbering changes the comment labels, not the captions
+    let restarted = game.kifu_figures(KifuOptions { restart_numbering: true, ..options.clone() });
+    let labels: Vec<&str> = restarted.iter().map(|f| f.caption.as_str()).collect();
+    if labels != captions || figure(&restarted, expected[2]).map(|f| f.comments) != Some(vec!["3: A fight starts.".to_string()]) {
+        failures.push("restarted numbering does not relabel comments".to_string());
+    }
+    let without = game.kifu_figures(KifuOptions { variations: false, comments: false, ..options.clone() });
+    if without.len() != 3 || without.iter().any(|f| !f.comments.is_empty()) {
+        failures.push(format!("{} figures without variations and comments", without.len()));
+    }
+
+    // Rendering from inside a side branch leaves the cursor where it was
+    game.jump_to_move_number(2);
+    game.go_forward(1);
+    game.go_forward(0);
+    let start = game.get_current_node().get_id();
+    let pdf = game.to_pdf(options).expect("pdf renders");
+    std::fs::write(std::env::temp_dir().join("qidao-variations.pdf"), &pdf).unwrap();
+    if game.get_current_node().get_id() != start {
+        failures.push("to_pdf moved the cursor off the side branch".to_string());
+    }
+    if page_count(&pdf) != 3 {
+        failures.push(format!("{} pages for 5 figures", page_count(&pdf)));
+    }
+    failures
+}
+
+/// A game whose MN jumps ahead and then starts again from 1: every renumbering opens a new
+/// diagram, so each caption gives one run of numbers and the comments match the stones.
+fn check_move_numbers() -> Vec<String> {
+    let sgf = "(;SZ[9];B[aa];W[ba];B[ca]MN[20];W[da];B[ea]C[Ko.];W[fa]MN[1];B[ga];W[ha]C[Seki.])";
+    let game = Game::from_sgf(sgf.to_string()).unwrap();
+    let options = KifuOptions { moves_per_diagram: 5, ..default_kifu_options() };
+    let mut failures = Vec::new();
+
+    let figures = game.kifu_figures(options.clone());
+    let captions: Vec<&str> = figures.iter().map(|f| f.caption.as_str()).collect();
+    if captions != ["Diagram 1 (1\u{2013}2)", "Diagram 2 (20\u{2013}22)", "Diagram 3 (1\u{2013}3)"] {
+        failures.push(format!("renumbered figures {:?}", captions));
+    }
+    let comments: Vec<Vec<String>> = figures.into_iter().map(|f| f.comments).collect();
+    if comments[1..] != [vec!["22: Ko.".to_string()], vec!["3: Seki.".to_string()]] {
+        failures.push(format!("renumbered comments {:?}", comments));
+    }
+    let restarted = game.kifu_figures(KifuOptions { restart_numbering: true, ..options.clone() });
+    let comments: Vec<Vec<String>> = restarted.into_iter().map(|f| f.comments).collect();
+    if comments[1..] != [vec!["3: Ko.".to_string()], vec!["3: Seki.".to_string()]] {
+        failures.push(format!("restarted renumbered comments {:?}", comments));
+    }
+    if page_count(&game.to_pdf(options).expect("pdf renders")) != 2 {
+        failures.push("renumbered game is not two pages".to_string());
+    }
+    failures
+}
@@ -0,0 +1,85 @@
+//! Decodes properties into their typed form, and stores typed properties on a node, saves
+//! the game and reads them back, checking that escaped text and compose values survive.
+//!
+//! Usage: cargo run --bin test-properties
+
+use qidao_core::{
+    Annotation, Game, GameInfoField, Label, MarkupShape, Move, Point, Property, Segment, StoneColor,
+};
+
+fn p(x: u32, y: u32) -> Point {
+    Point { x, y }
+}
+
+/// (name, root properties of a 9x9 game, how the first of them decodes)
+fn decoded() -> Vec<(&'static str, &'static str, Property)> {
+    vec![
+        ("move", "B[cd]", Property::Move { color: StoneColor::Black, mv: Move::Play { point: p(2, 3) } }),
+        ("pass", "W[]", Property::Move { color: StoneColor::White, mv: Move::Pass }),
+        // Compressed point lists expand row by row
+        (
+            "point list",
+            "AW[aa:bb]",
+            Property::Setup { color: Some(StoneColor::White), points: vec![p(0, 0), p(1, 0), p(0, 1), p(1, 1)] },
+        ),
+        ("label", r"LB[ee:a\:b\]c\\d]", Property::Labels { labels: vec![Label { point: p(4, 4), text: r"a:b]c\d".into() }] }),
+        ("arrow", "AR[aa:cc]", Property::Arrows { segments: vec![Segment { from: p(0, 0), to: p(2, 2) }] }),
+        ("emphasis", "GB[2]", Property::Annotation { annotation: Annotation::GoodForBlack, emphasized: true }),
+        // A soft line break disappears; a hard one stays
+        ("comment", "C[one \\\ntwo\nthree]", Property::Comment { text: "one two\nthree".into() }),
+        ("komi", "KM[6.5]", Property::Komi { komi: 6.5 }),
+        ("off the board", "TR[jj]", Property::Unknown { identifier: "TR".into(), values: vec!["jj".into()] }),
+        ("unknown", r"XX[a\]b][c]", Property::Unknown { identifier: "XX".into(), values: vec!["a]b".into(), "c".into()] }),
+    ]
+}
+
+/// Typed properties that must come back unchanged from a saved game.
+fn stored() -> Vec<Property> {
+    vec![
+        Property::Comment { text: "brackets ] and \\ backslashes\nover two lines".into() },
+        Property::NodeName { text: "a: b".into() },
+        Property::Labels {
+            labels: vec![Label { point: p(0, 8), text: "x:y]".into() }, Label { point: p(8, 0), text: "7".into() }],
+        },
+        Property::Markup { shape: MarkupShape::Square, points: vec![p(3, 3), p(5, 5)] },
+        Property::Lines { segments: vec![Segment { from: p(0, 0), to: p(8, 8) }] },
+        Property::Annotation { annotation: Annotation::Unclear, emphasized: false },
+        Property::GameInfo { field: GameInfoField::Event, text: "Meijin [final]".into() },
+        Property::TimeLeft { color: StoneColor::White, seconds: 30.5 },
+        Property::Unknown { identifier: "XX".into(), values: vec!["a]b".into(), "".into()] },
+    ]
+}
+
+fn main() {
+    let mut failures = 0;
+    for (name, props, expected) in decoded() {
+        let game = Game::from_sgf(format!("(;SZ[9]{})", props)).expect("case parses");
+        // SZ comes first
+        let found = game.get_typed_properties().into_iter().nth(1);
+        if found.as_ref() != Some(&expected) {
+            failures += 1;
+            println!("FAIL  {:<16} decoded as {:?}", name, found);
+        } else {
+            println!("ok    {}", name);
+        }
+    }
+
+    let game = Game::new(9).unwrap();
+    for property in stored() {
+        game.get_current_node().set_typed_property(property);
+    }
+    let sgf = game.to_sgf();
+    let reloaded = Game::from_sgf(sgf.clone()).expect("saved game parses").get_typed_properties();
+    let lost: Vec<Property> = stored().into_iter().filter(|p| !reloaded.contains(p)).collect();
+    if lost.is_empty() && Game::from_sgf(sgf.clone()).unwrap().to_sgf() == sgf {
+        println!("ok    round trip");
+    } else {
+        failures += 1;
+        println!("FAIL  round trip       {:?} lost from {}", lost, sgf);
+    }
+
+    if failures > 0 {
+        eprintln!("{} case(s) failed", failures);
+        std::process::exit(1);
+    }
+}
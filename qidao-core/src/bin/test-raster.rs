@@ -0,0 +1,54 @@
+//! Renders PNG and GIF exports of an asset game and checks their size and frames.
+//! Runs without a display; writes the images to a temporary directory for a look.
+//!
+//! Usage: cargo run --bin test-raster [game.sgf]
+
+use qidao_core::{default_animation_options, default_diagram_options, Game, MoveRange};
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| format!("{}/../assets/games/test.sgf", env!("CARGO_MANIFEST_DIR")));
+    let game = Game::from_sgf(std::fs::read_to_string(&path).expect("game file")).expect("game parses");
+    let out = std::env::temp_dir();
+    let mut failures = Vec::new();
+
+    game.jump_to_move_number(game.get_max_move_count().min(30));
+    let png = game.to_png(default_diagram_options(), 600).expect("png renders");
+    std::fs::write(out.join("qidao-position.png"), &png).unwrap();
+    // IHDR starts at byte 16 with the big-endian width and height
+    let size = |at: usize| u32::from_be_bytes(png[at..at + 4].try_into().unwrap());
+    if !png.starts_with(b"\x89PNG\r\n\x1a\n") || size(16) != 600 || size(20) < 500 {
+        failures.push(format!("position png is {}x{}", size(16), size(20)));
+    }
+
+    let options = default_diagram_options();
+    let figure = qidao_core::DiagramOptions { move_range: Some(MoveRange { first: 1, last: 30 }), ..options };
+    if game.to_png(figure, 16_000).is_ok() {
+        failures.push("oversized png was accepted".to_string());
+    }
+
+    let animation = qidao_core::AnimationOptions { width: 240, move_numbers: true, ..default_animation_options() };
+    let start = game.get_current_node().get_id();
+    let gif = game.to_gif(animation).expect("gif renders");
+    std::fs::write(out.join("qidao-replay.gif"), &gif).unwrap();
+    if game.get_current_node().get_id() != start {
+        failures.push("to_gif moved the current node".to_string());
+    }
+    let mut decoder = gif::DecodeOptions::new().read_info(gif.as_slice()).expect("gif decodes");
+    let mut frames = 0;
+    while decoder.read_next_frame().expect("frame decodes").is_some() {
+        frames += 1;
+    }
+    let expected = game.get_max_move_count() + 1;
+    if frames != expected || decoder.width() != 240 {
+        failures.push(format!("gif has {} frames of width {}, expected {}", frames, decoder.width(), expected));
+    }
+
+    if failures.is_empty() {
+        println!("ok    png {} bytes, gif {} frames in {}", png.len(), frames, out.display());
+    } else {
+        eprintln!("FAIL  {}", failures.join("; "));
+        std::process::exit(1);
+    }
+}
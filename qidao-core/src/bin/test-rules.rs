@@ -0,0 +1,54 @@
+//! Sets each rule set on a new game and checks what `RU` and KataGo queries are told,
+//! and that the rules come back unchanged from the saved SGF.
+//!
+//! Usage: cargo run --bin test-rules
+
+use qidao_core::{parse_rules, Game, RuleSet, SuperkoPolicy};
+
+/// Ko rule KataGo plays under each of its rule set names.
+fn katago_ko_rule(name: &str) -> Option<SuperkoPolicy> {
+    match name {
+        "chinese" | "japanese" | "korean" => Some(SuperkoPolicy::None),
+        "aga" | "new-zealand" => Some(SuperkoPolicy::Situational),
+        "tromp-taylor" => Some(SuperkoPolicy::Positional),
+        _ => None,
+    }
+}
+
+fn main() {
+    let mut failures = 0;
+    for set in RuleSet::ALL {
+        let rules = set.rules();
+        let game = Game::new(9).unwrap();
+        game.set_rules(rules);
+        let sgf = game.to_sgf();
+
+        let mut problems = Vec::new();
+        if !sgf.contains(&format!("RU[{}]", set.sgf_name())) {
+            problems.push(format!("saved as {}", sgf));
+        }
+        let reloaded = Game::from_sgf(sgf).expect("saved game parses").get_rules();
+        if reloaded != rules {
+            problems.push(format!("reloaded as {:?}", reloaded.name));
+        }
+
+        let query: serde_json::Value = serde_json::from_str(&game.get_analysis_query("q".into()).unwrap()).unwrap();
+        let sent = query["rules"].as_str().unwrap_or_default().to_string();
+        let ko_rule = katago_ko_rule(&sent).or_else(|| parse_rules(sent.clone()).map(|r| r.ko_rule));
+        if ko_rule != Some(rules.ko_rule) {
+            problems.push(format!("KataGo is sent {} for {:?}", sent, rules.ko_rule));
+        }
+
+        if problems.is_empty() {
+            println!("ok    {:<12} RU[{}] rules {}", format!("{:?}", set), set.sgf_name(), sent);
+        } else {
+            failures += 1;
+            println!("FAIL  {:<12} {}", format!("{:?}", set), problems.join("; "));
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{} rule set(s) failed", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,82 @@
+//! Scores small finished positions under area and territory rules and checks the regions,
+//! dead-stone marking, seki and stone taxes and the handicap bonus.
+//!
+//! Usage: cargo run --bin test-scoring
+
+use qidao_core::Game;
+
+/// Black holds the two left columns and White the right one, with walls on c and d
+const SPLIT: &str = "SZ[5]AB[ca][cb][cc][cd][ce]AW[da][db][dc][dd][de]";
+/// One-eyed groups sharing the liberty da between them: Black's eye is aa, White's fa
+const SEKI: &str = "SZ[6:2]AB[ba][ca][ab][bb][cb]AW[ea][db][eb][fb]";
+
+/// (name, root properties, chains toggled dead, result, black / white territory and neutral
+/// points, Black's prisoners, handicap bonus)
+type Case = (&'static str, String, Vec<(u32, u32)>, &'static str, [usize; 3], u32, f64);
+
+fn cases() -> Vec<Case> {
+    vec![
+        ("area", format!("{}RU[Chinese]KM[0.5]", SPLIT), vec![], "B+4.5", [10, 5, 0], 0, 0.0),
+        ("territory", format!("{}RU[Japanese]KM[0.5]", SPLIT), vec![], "B+4.5", [10, 5, 0], 0, 0.0),
+        // A white stone left in Black's area spoils it until it is marked dead
+        ("live invader", format!("{}AW[aa]RU[Japanese]KM[0.5]", SPLIT), vec![], "W+5.5", [0, 5, 9], 0, 0.0),
+        ("dead invader", format!("{}AW[aa]RU[Japanese]KM[0.5]", SPLIT), vec![(0, 0)], "B+5.5", [10, 5, 0], 1, 0.0),
+        (
+            "revived invader",
+            format!("{}AW[aa]RU[Japanese]KM[0.5]", SPLIT),
+            vec![(0, 0), (0, 0)],
+            "W+5.5",
+            [0, 5, 9],
+            0,
+            0.0,
+        ),
+        ("dead invader, area", format!("{}AW[aa]RU[Chinese]KM[0.5]", SPLIT), vec![(0, 0)], "B+4.5", [10, 5, 0], 1, 0.0),
+        // Stone scoring taxes each living group two points: 15 - 2 against 10 - 2 + 0.5
+        ("stone tax", format!("{}RU[koSIMPLEscoreAREAtaxALLsui0whb0]KM[0.5]", SPLIT), vec![], "B+4.5", [10, 5, 0], 0, 0.0),
+        ("handicap N", format!("{}RU[Chinese]HA[3]KM[0.5]", SPLIT), vec![], "B+1.5", [10, 5, 0], 0, 3.0),
+        ("handicap N-1", format!("{}RU[AGA]HA[3]KM[0.5]", SPLIT), vec![], "B+2.5", [10, 5, 0], 0, 2.0),
+        ("handicap territory", format!("{}RU[Japanese]HA[3]KM[0.5]", SPLIT), vec![], "B+4.5", [10, 5, 0], 0, 0.0),
+        // Area rules count the eyes of groups in seki, territory rules do not
+        ("seki, area", format!("{}RU[Chinese]KM[0.5]", SEKI), vec![], "B+0.5", [1, 1, 1], 0, 0.0),
+        ("seki, territory", format!("{}RU[Japanese]KM[0.5]", SEKI), vec![], "W+0.5", [0, 0, 3], 0, 0.0),
+    ]
+}
+
+fn main() {
+    let mut failures = 0;
+    for (name, root, dead, result, [black, white, neutral], prisoners, bonus) in cases() {
+        let game = Game::from_sgf(format!("(;{})", root)).expect("case parses");
+        let session = game.start_scoring();
+        let mut report = session.score();
+        for (x, y) in dead {
+            report = session.toggle_dead_group(x, y);
+        }
+
+        let regions = [report.black_territory.len(), report.white_territory.len(), report.neutral_points.len()];
+        let mut problems = Vec::new();
+        if report.result != result {
+            problems.push(format!("result {}", report.result));
+        }
+        if regions != [black, white, neutral] {
+            problems.push(format!("regions {:?}", regions));
+        }
+        if report.black_prisoners != prisoners || report.dead_stones.len() as u32 != prisoners {
+            problems.push(format!("{} prisoners, {} dead", report.black_prisoners, report.dead_stones.len()));
+        }
+        if report.handicap_bonus != bonus {
+            problems.push(format!("handicap bonus {}", report.handicap_bonus));
+        }
+
+        if problems.is_empty() {
+            println!("ok    {:<20} {}", name, report.result);
+        } else {
+            failures += 1;
+            println!("FAIL  {:<20} {}", name, problems.join("; "));
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{} case(s) failed", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,77 @@
+//! Replays short cycles and checks which superko policies reject the move that closes them,
+//! through `Game::check_move`, `Game::place_stone` and `validate_sgf`.
+//!
+//! Usage: cargo run --bin test-superko
+
+use qidao_core::{validate_sgf, DiagnosticKind, Game, Legality, StoneColor, SuperkoPolicy};
+
+use StoneColor::{Black, White};
+
+const POLICIES: [(SuperkoPolicy, &str); 3] = [
+    (SuperkoPolicy::None, "Japanese"),
+    (SuperkoPolicy::Positional, "Chinese"),
+    (SuperkoPolicy::Situational, "AGA"),
+];
+
+/// (name, game up to the cycle, closing move, whether the position it repeats had the same
+/// side to move as after the move)
+type Case = (&'static str, &'static str, (u32, u32, StoneColor), bool);
+
+fn cases() -> Vec<Case> {
+    vec![
+        // Black's recapture brings back the position after move 1, White to move both times
+        ("situational", "(;SZ[2];B[aa];W[bb];B[ba];W[ab];B[aa];W[ba])", (0, 0, Black), true),
+        // Black's move rebuilds the position after move 2, which had Black to move
+        ("positional only", "(;SZ[3:1];B[aa];W[ca];B[ba];W[ca])", (0, 0, Black), false),
+        // White's recapture brings back the setup position of the root, Black to move
+        ("root position", "(;SZ[2]AW[aa];B[bb];W[ba];B[ab];W[aa];B[ba])", (0, 0, White), true),
+    ]
+}
+
+fn main() {
+    let mut failures = 0;
+    for (name, sgf, (x, y, color), situational) in cases() {
+        let mut problems = Vec::new();
+        for (policy, rules) in POLICIES {
+            let expected = match policy {
+                SuperkoPolicy::None => Legality::Legal,
+                SuperkoPolicy::Positional => Legality::Superko,
+                SuperkoPolicy::Situational if situational => Legality::Superko,
+                SuperkoPolicy::Situational => Legality::Legal,
+            };
+
+            let game = Game::from_sgf(sgf.to_string()).expect("case parses");
+            game.set_superko_policy(policy);
+            game.jump_to_move_number(game.get_max_move_count());
+            let checked = game.check_move(x, y, color);
+            let played = game.place_stone(x, y, color).is_ok();
+            if checked != expected || played != (expected == Legality::Legal) {
+                problems.push(format!("{:?}: checked {:?}, played {}", policy, checked, played));
+            }
+
+            // The validator reads the policy from RU and flags the recorded repetition
+            let coord = format!("{}{}", (b'a' + x as u8) as char, (b'a' + y as u8) as char);
+            let id = if color == Black { "B" } else { "W" };
+            let recorded = format!("(;RU[{}]{};{}[{}])", rules, &sgf[2..sgf.len() - 1], id, coord);
+            let flagged = validate_sgf(recorded)
+                .diagnostics
+                .iter()
+                .any(|d| d.kind == DiagnosticKind::IllegalMove { reason: Legality::Superko });
+            if flagged != (expected == Legality::Superko) {
+                problems.push(format!("{:?}: validator flagged {}", policy, flagged));
+            }
+        }
+
+        if problems.is_empty() {
+            println!("ok    {}", name);
+        } else {
+            failures += 1;
+            println!("FAIL  {:<16} {}", name, problems.join("; "));
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{} case(s) failed", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,60 @@
+//! Walks games with handicap, `PL`, setup nodes and root moves and checks the side to move
+//! at every node and the initial player sent to KataGo.
+//!
+//! Usage: cargo run --bin test-turns
+
+use qidao_core::{Game, StoneColor};
+
+use StoneColor::{Black, White};
+
+/// (name, main line, initial player, side to move at the root and after each node)
+type Case = (&'static str, &'static str, StoneColor, Vec<StoneColor>);
+
+fn cases() -> Vec<Case> {
+    vec![
+        ("even", "(;SZ[9];B[ee];W[cc])", Black, vec![Black, White, Black]),
+        ("handicap", "(;SZ[9]HA[2]AB[cc][gg];W[ee];B[ce])", White, vec![White, Black, White]),
+        // The root's own move is played, so the side to move there is the other one
+        ("root move", "(;SZ[9]B[ee];W[cc])", Black, vec![White, Black]),
+        ("handicap root move", "(;SZ[9]HA[2]AB[cc][gg]W[ee];B[ce])", White, vec![Black, White]),
+        ("PL at the root", "(;SZ[9]PL[W];W[ee])", White, vec![White, Black]),
+        ("setup node", "(;SZ[9];B[ee];AB[cc];W[gg])", Black, vec![Black, White, White, Black]),
+        ("PL on a setup node", "(;SZ[9];B[ee];AB[cc]PL[B];B[gg])", Black, vec![Black, White, Black, White]),
+    ]
+}
+
+fn main() {
+    let mut failures = 0;
+    for (name, sgf, initial, turns) in cases() {
+        let game = Game::from_sgf(sgf.to_string()).expect("case parses");
+        let mut seen = vec![game.get_next_color()];
+        while game.go_forward(0) {
+            seen.push(game.get_next_color());
+        }
+
+        let mut problems = Vec::new();
+        if seen != turns {
+            problems.push(format!("sides to move {:?}", seen));
+        }
+        if game.get_initial_player() != initial {
+            problems.push(format!("initial player {:?}", game.get_initial_player()));
+        }
+        let query: serde_json::Value = serde_json::from_str(&game.get_analysis_query("q".into()).unwrap()).unwrap();
+        let sent = if initial == Black { "B" } else { "W" };
+        if query["initialPlayer"] != sent {
+            problems.push(format!("query initialPlayer {}", query["initialPlayer"]));
+        }
+
+        if problems.is_empty() {
+            println!("ok    {}", name);
+        } else {
+            failures += 1;
+            println!("FAIL  {:<20} {}", name, problems.join("; "));
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{} case(s) failed", failures);
+        std::process::exit(1);
+    }
+}
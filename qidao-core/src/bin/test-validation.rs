@@ -0,0 +1,70 @@
+//! Runs `validate_sgf` over damaged SGF snippets and checks what it reports.
+//!
+//! Usage: cargo run --bin test-validation
+
+use qidao_core::{validate_sgf, DiagnosticKind, Game, Legality};
+
+/// (name, SGF, expected diagnostic kinds in order, whether it needs repairs to load)
+fn cases() -> Vec<(&'static str, &'static str, Vec<DiagnosticKind>, bool)> {
+    use DiagnosticKind::*;
+    vec![
+        ("clean", "(;SZ[9];B[ee];W[ec](;B[aa])(;B[bb]))", vec![], false),
+        ("truncated value", "(;SZ[9];B[ee];W[ec]C[hello", vec![Truncated, Truncated], true),
+        ("truncated trees", "(;SZ[9];B[ee](;W[ec])(;W[cc];B[dd]", vec![Truncated], true),
+        ("two moves", "(;SZ[9];B[ee]W[ec])", vec![DuplicateMove], false),
+        ("occupied", "(;SZ[9];B[ee];W[ee])", vec![OccupiedPoint], false),
+        ("suicide", "(;SZ[9]AB[ba][ab];W[aa])", vec![IllegalMove { reason: Legality::Suicide }], false),
+        (
+            "ko",
+            "(;SZ[9]AB[bc][cb][cd][dc]AW[ec][db][dd];W[cc];B[dc])",
+            vec![IllegalMove { reason: Legality::Ko }],
+            false,
+        ),
+        (
+            // The repetition is played as boards play it, so the next move lands on a stone
+            "superko",
+            "(;SZ[2];B[aa];W[bb];B[ba];W[ab];B[aa];W[ba];B[aa];W[aa])",
+            vec![IllegalMove { reason: Legality::Superko }, OccupiedPoint],
+            false,
+        ),
+        ("bad coordinates", "(;SZ[9];B[zz];W[e])", vec![BadCoordinate, BadCoordinate], false),
+        ("wrong types", "(;KM[abc]C[a]C[b])", vec![WrongPropertyType, DuplicateProperty], false),
+        // Lower-case letters in FF[3] identifiers are dropped, so these are SZ, AB and W
+        ("FF[3] identifiers", "(;FF[3]SiZe[9]AddBlack[ee][cc];White[dd];Black[ee])", vec![OccupiedPoint], false),
+        ("junk", "junk(;SZ[9];B[ee])", vec![Syntax], false),
+    ]
+}
+
+fn main() {
+    let mut failures = 0;
+    for (name, sgf, expected, repaired) in cases() {
+        let report = validate_sgf(sgf.to_string());
+        let kinds: Vec<_> = report.diagnostics.iter().map(|d| d.kind).collect();
+
+        let mut problems = Vec::new();
+        if kinds != expected {
+            problems.push(format!("reported {:?}", kinds));
+        }
+        if report.altered != repaired {
+            problems.push(format!("altered is {}", report.altered));
+        }
+        if report.diagnostics.iter().any(|d| d.offset.is_none_or(|o| o as usize > sgf.len())) {
+            problems.push("offset missing or past the end".to_string());
+        }
+        if !name.starts_with("junk") && Game::from_sgf(sgf.to_string()).is_err() {
+            problems.push("does not load".to_string());
+        }
+
+        if problems.is_empty() {
+            println!("ok    {}", name);
+        } else {
+            failures += 1;
+            println!("FAIL  {:<16} {}", name, problems.join("; "));
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{} case(s) failed", failures);
+        std::process::exit(1);
+    }
+}
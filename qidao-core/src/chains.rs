@@ -0,0 +1,102 @@
+/// Incremental chain bookkeeping for `Board`.
+///
+/// Every stone points at the representative ("head") of its chain, and the stones of a
+/// chain form a circular list through `next`, so merging and removing chains never
+/// allocates. Liberties are tracked as pseudo-liberties: the number of (stone, empty
+/// neighbor) pairs of a chain. A chain has no liberties exactly when that count is zero;
+/// the distinct liberties are only counted when asked for.
+#[derive(Clone, Debug)]
+pub(crate) struct Chains {
+    head: Vec<u32>,
+    next: Vec<u32>,
+    /// Indexed by head; only meaningful for points that are chain heads.
+    size: Vec<u32>,
+    pseudo_liberties: Vec<u32>,
+}
+
+impl Chains {
+    pub(crate) fn new(points: usize) -> Self {
+        Self {
+            head: (0..points as u32).collect(),
+            next: (0..points as u32).collect(),
+            size: vec![0; points],
+            pseudo_liberties: vec![0; points],
+        }
+    }
+
+    pub(crate) fn head(&self, idx: usize) -> usize {
+        self.head[idx] as usize
+    }
+
+    pub(crate) fn size(&self, idx: usize) -> usize {
+        self.size[self.head(idx)] as usize
+    }
+
+    pub(crate) fn has_liberties(&self, idx: usize) -> bool {
+        self.pseudo_liberties[self.head(idx)] > 0
+    }
+
+    /// Starts a one-stone chain at `idx` with `liberties` empty neighbors.
+    pub(crate) fn add_stone(&mut self, idx: usize, liberties: u32) {
+        self.head[idx] = idx as u32;
+        self.next[idx] = idx as u32;
+        self.size[idx] = 1;
+        self.pseudo_liberties[idx] = liberties;
+    }
+
+    /// A neighboring point of the chain containing `idx` was filled.
+    pub(crate) fn remove_liberty(&mut self, idx: usize) {
+        let head = self.head(idx);
+        self.pseudo_liberties[head] -= 1;
+    }
+
+    /// A neighboring point of the chain containing `idx` was emptied.
+    pub(crate) fn add_liberty(&mut self, idx: usize) {
+        let head = self.head(idx);
+        self.pseudo_liberties[head] += 1;
+    }
+
+    /// Joins the chains containing `a` and `b`, relabelling the smaller one.
+    pub(crate) fn merge(&mut self, a: usize, b: usize) {
+        let (mut keep, mut absorb) = (self.head(a), self.head(b));
+        if keep == absorb {
+            return;
+        }
+        if self.size[keep] < self.size[absorb] {
+            std::mem::swap(&mut keep, &mut absorb);
+        }
+        let mut idx = absorb;
+        loop {
+            self.head[idx] = keep as u32;
+            idx = self.next[idx] as usize;
+            if idx == absorb {
+                break;
+            }
+        }
+        self.next.swap(keep, absorb);
+        self.size[keep] += self.size[absorb];
+        self.pseudo_liberties[keep] += self.pseudo_liberties[absorb];
+    }
+
+    /// Stones of the chain containing `idx`, starting with `idx` itself.
+    pub(crate) fn stones(&self, idx: usize) -> ChainStones<'_> {
+        ChainStones { chains: self, start: idx, current: Some(idx) }
+    }
+}
+
+pub(crate) struct ChainStones<'a> {
+    chains: &'a Chains,
+    start: usize,
+    current: Option<usize>,
+}
+
+impl Iterator for ChainStones<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let idx = self.current?;
+        let next = self.chains.next[idx] as usize;
+        self.current = (next != self.start).then_some(next);
+        Some(idx)
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{parse_game_trees, Game, GameMetadata, SgfError, SgfNode};
+
+/// All game trees of one SGF file. Games are opened once and shared, so edits made through
+/// a `Game` from `get_game` are part of what `to_sgf` writes.
+#[derive(uniffi::Object)]
+pub struct SgfCollection {
+    games: Mutex<Vec<Arc<Game>>>,
+}
+
+impl SgfCollection {
+    pub(crate) fn from_roots(roots: Vec<Arc<SgfNode>>) -> Arc<Self> {
+        Arc::new(Self { games: Mutex::new(roots.into_iter().map(Game::from_root).collect()) })
+    }
+}
+
+#[uniffi::export]
+impl SgfCollection {
+    #[uniffi::constructor]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self { games: Mutex::new(vec![]) })
+    }
+
+    /// Reads every game tree in the file, in file order.
+    #[uniffi::constructor]
+    pub fn from_sgf(sgf_content: String) -> Result<Arc<Self>, SgfError> {
+        Ok(Self::from_roots(parse_game_trees(sgf_content, false)?))
+    }
+
+    pub fn get_game_count(&self) -> u32 {
+        self.games.lock().unwrap().len() as u32
+    }
+
+    /// Root information of every game, in collection order.
+    pub fn get_all_metadata(&self) -> Vec<GameMetadata> {
+        self.games.lock().unwrap().iter().map(|g| g.get_metadata()).collect()
+    }
+
+    pub fn get_metadata(&self, index: u32) -> Result<GameMetadata, SgfError> {
+        Ok(self.get_game(index)?.get_metadata())
+    }
+
+    pub fn get_game(&self, index: u32) -> Result<Arc<Game>, SgfError> {
+        self.games.lock().unwrap().get(index as usize).cloned().ok_or_else(|| out_of_range(index))
+    }
+
+    /// Appends a game to the end of the collection.
+    pub fn add_game(&self, game: Arc<Game>) {
+        self.games.lock().unwrap().push(game);
+    }
+
+    /// Inserts a game before `index`; an index equal to the game count appends.
+    pub fn insert_game(&self, index: u32, game: Arc<Game>) -> Result<(), SgfError> {
+        let mut games = self.games.lock().unwrap();
+        if index as usize > games.len() {
+            return Err(out_of_range(index));
+        }
+        games.insert(index as usize, game);
+        Ok(())
+    }
+
+    pub fn remove_game(&self, index: u32) -> Result<Arc<Game>, SgfError> {
+        let mut games = self.games.lock().unwrap();
+        if index as usize >= games.len() {
+            return Err(out_of_range(index));
+        }
+        Ok(games.remove(index as usize))
+    }
+
+    /// Moves the game at `from` so that it ends up at index `to`.
+    pub fn move_game(&self, from: u32, to: u32) -> Result<(), SgfError> {
+        let mut games = self.games.lock().unwrap();
+        for index in [from, to] {
+            if index as usize >= games.len() {
+                return Err(out_of_range(index));
+            }
+        }
+        let game = games.remove(from as usize);
+        games.insert(to as usize, game);
+        Ok(())
+    }
+
+    /// Writes all games, one game tree per line.
+    pub fn to_sgf(&self) -> String {
+        let games = self.games.lock().unwrap();
+        games.iter().map(|g| g.to_sgf() + "\n").collect()
+    }
+}
+
+fn out_of_range(index: u32) -> SgfError {
+    SgfError::ParseError { message: format!("Game index {} out of range", index) }
+}
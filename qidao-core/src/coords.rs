@@ -0,0 +1,239 @@
+use crate::SgfError;
+
+/// Largest board dimension expressible with SGF's `a-z` + `A-Z` coordinate letters.
+pub const MAX_BOARD_SIZE: u32 = 52;
+
+/// GTP column letters; 'I' is skipped.
+const GTP_COLUMNS: &[u8] = b"ABCDEFGHJKLMNOPQRSTUVWXYZ";
+
+const CJK_DIGITS: [char; 10] = ['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+
+/// A board intersection. (0, 0) is the upper-left corner, as in SGF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Record)]
+pub struct Point {
+    pub x: u32,
+    pub y: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum Move {
+    Play { point: Point },
+    Pass,
+}
+
+/// Textual coordinate systems understood by `format_move` and `parse_move`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum Notation {
+    /// SGF letters: "pd", with "" (or "tt" up to 19x19) for a pass.
+    Sgf,
+    /// GTP vertices, also the usual human notation: "Q16", "pass".
+    /// Columns skip 'I' and continue with two letters past 'Z' ("AA", "AB", ...).
+    Gtp,
+    /// KataGo's zero-based "(x,y)" form, counted from the upper-left corner.
+    /// Parsing also accepts GTP vertices, which KataGo itself reports.
+    KataGo,
+    /// Traditional Japanese and Chinese numbering, both one-based: the column counted from
+    /// the right, then the row from the top. "4-4" is the upper-right star point, Q16.
+    Numeric,
+    /// The numeric form in Chinese numerals: "四之四"; "の" is accepted as separator.
+    Cjk,
+}
+
+/// Formats a move in the given notation, failing if it lies outside a `width`x`height` board.
+#[uniffi::export]
+pub fn format_move(mv: Move, notation: Notation, width: u32, height: u32) -> Result<String, SgfError> {
+    check_size(width, height)?;
+    let point = match mv {
+        Move::Pass => {
+            return Ok(match notation {
+                Notation::Sgf => String::new(),
+                Notation::Cjk => "停一手".to_string(),
+                Notation::Gtp | Notation::KataGo | Notation::Numeric => "pass".to_string(),
+            })
+        }
+        Move::Play { point } => point,
+    };
+    if point.x >= width || point.y >= height {
+        return Err(SgfError::ParseError {
+            message: format!("Point ({}, {}) is outside the {}x{} board", point.x, point.y, width, height),
+        });
+    }
+    Ok(match notation {
+        Notation::Sgf => sgf_coord(point.x, point.y),
+        Notation::Gtp => point_to_gtp(point.x, point.y, height),
+        Notation::KataGo => format!("({},{})", point.x, point.y),
+        Notation::Numeric => format!("{}-{}", width - point.x, point.y + 1),
+        Notation::Cjk => format!("{}之{}", cjk_number(width - point.x), cjk_number(point.y + 1)),
+    })
+}
+
+/// Parses a move written in the given notation on a `width`x`height` board.
+#[uniffi::export]
+pub fn parse_move(text: String, notation: Notation, width: u32, height: u32) -> Result<Move, SgfError> {
+    check_size(width, height)?;
+    let invalid = || SgfError::ParseError {
+        message: format!("Invalid {:?} coordinate: \"{}\"", notation, text),
+    };
+    let value = if notation == Notation::Sgf { text.as_str() } else { text.trim() };
+
+    let is_pass = match notation {
+        Notation::Sgf => is_sgf_pass(value, width, height),
+        Notation::Cjk => ["停一手", "パス"].contains(&value) || value.eq_ignore_ascii_case("pass"),
+        Notation::Gtp | Notation::KataGo | Notation::Numeric => value.eq_ignore_ascii_case("pass"),
+    };
+    if is_pass {
+        return Ok(Move::Pass);
+    }
+
+    let (x, y) = match notation {
+        Notation::Sgf => sgf_to_point(value).ok_or_else(invalid)?,
+        Notation::Gtp => gtp_to_point(value, height).ok_or_else(invalid)?,
+        Notation::KataGo => match value.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+            Some(inner) => {
+                let (x, y) = inner.split_once(',').ok_or_else(invalid)?;
+                (x.trim().parse().map_err(|_| invalid())?, y.trim().parse().map_err(|_| invalid())?)
+            }
+            None => gtp_to_point(value, height).ok_or_else(invalid)?,
+        },
+        Notation::Numeric => {
+            let (col, row) = value.split_once('-').ok_or_else(invalid)?;
+            let col: u32 = col.trim().parse().map_err(|_| invalid())?;
+            let row: u32 = row.trim().parse().map_err(|_| invalid())?;
+            (width.checked_sub(col).ok_or_else(invalid)?, row.checked_sub(1).ok_or_else(invalid)?)
+        }
+        Notation::Cjk => {
+            let (col, row) = value.split_once(['之', 'の']).ok_or_else(invalid)?;
+            let col = parse_cjk_number(col.trim()).ok_or_else(invalid)?;
+            let row = parse_cjk_number(row.trim()).ok_or_else(invalid)?;
+            (width.checked_sub(col).ok_or_else(invalid)?, row.checked_sub(1).ok_or_else(invalid)?)
+        }
+    };
+
+    if x >= width || y >= height {
+        return Err(SgfError::ParseError {
+            message: format!("\"{}\" is outside the {}x{} board", text, width, height),
+        });
+    }
+    Ok(Move::Play { point: Point { x, y } })
+}
+
+pub(crate) fn check_size(width: u32, height: u32) -> Result<(), SgfError> {
+    let valid = 1..=MAX_BOARD_SIZE;
+    if valid.contains(&width) && valid.contains(&height) {
+        Ok(())
+    } else {
+        Err(SgfError::ParseError { message: format!("Unsupported board size {}x{}", width, height) })
+    }
+}
+
+// --- SGF ---
+
+/// An empty move value is a pass; FF[3] files also use `tt` on boards up to 19x19.
+pub(crate) fn is_sgf_pass(value: &str, width: u32, height: u32) -> bool {
+    value.is_empty() || (value == "tt" && width <= 19 && height <= 19)
+}
+
+fn sgf_letter(i: u32) -> char {
+    if i < 26 {
+        (b'a' + i as u8) as char
+    } else {
+        (b'A' + (i - 26) as u8) as char
+    }
+}
+
+fn sgf_letter_value(c: u8) -> Option<u32> {
+    match c {
+        b'a'..=b'z' => Some((c - b'a') as u32),
+        b'A'..=b'Z' => Some((c - b'A') as u32 + 26),
+        _ => None,
+    }
+}
+
+pub(crate) fn sgf_coord(x: u32, y: u32) -> String {
+    format!("{}{}", sgf_letter(x), sgf_letter(y))
+}
+
+/// Parses a two-letter SGF point ("pd", "aC") without bounds checking.
+pub(crate) fn sgf_to_point(value: &str) -> Option<(u32, u32)> {
+    match value.as_bytes() {
+        [x, y] => Some((sgf_letter_value(*x)?, sgf_letter_value(*y)?)),
+        _ => None,
+    }
+}
+
+// --- GTP ---
+
+/// GTP column name; past 'Z' KataGo continues with two letters ("AA", "AB", ...).
+pub(crate) fn gtp_column(x: u32) -> String {
+    let n = GTP_COLUMNS.len() as u32;
+    if x < n {
+        (GTP_COLUMNS[x as usize] as char).to_string()
+    } else {
+        format!("{}{}", GTP_COLUMNS[(x / n - 1) as usize] as char, GTP_COLUMNS[(x % n) as usize] as char)
+    }
+}
+
+pub(crate) fn point_to_gtp(x: u32, y: u32, height: u32) -> String {
+    format!("{}{}", gtp_column(x), height - y)
+}
+
+/// Parses a GTP vertex such as "Q16" or KataGo's "AB30" on wide boards.
+/// Only the row is checked against the board; callers check the column.
+fn gtp_to_point(vertex: &str, height: u32) -> Option<(u32, u32)> {
+    let vertex = vertex.to_ascii_uppercase();
+    let split = vertex.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = vertex.split_at(split);
+    let n = GTP_COLUMNS.len() as u32;
+    let index = |c: u8| GTP_COLUMNS.iter().position(|&l| l == c).map(|i| i as u32);
+    let x = match letters.as_bytes() {
+        [c] => index(*c)?,
+        [c1, c2] => (index(*c1)? + 1) * n + index(*c2)?,
+        _ => return None,
+    };
+    let row: u32 = digits.parse().ok()?;
+    if row == 0 || row > height {
+        return None;
+    }
+    Some((x, height - row))
+}
+
+/// GTP vertex for an SGF move value. Values that are neither a point on the
+/// board nor a pass are an error rather than being sent to the engine as passes.
+pub(crate) fn sgf_to_gtp(value: &str, width: u32, height: u32) -> Result<String, SgfError> {
+    let mv = parse_move(value.to_string(), Notation::Sgf, width, height)?;
+    format_move(mv, Notation::Gtp, width, height)
+}
+
+// --- Chinese numerals ---
+
+fn cjk_number(n: u32) -> String {
+    let (tens, units) = (n / 10, n % 10);
+    let mut out = String::new();
+    if tens > 1 {
+        out.push(CJK_DIGITS[tens as usize]);
+    }
+    if tens > 0 {
+        out.push('十');
+    }
+    if units > 0 || tens == 0 {
+        out.push(CJK_DIGITS[units as usize]);
+    }
+    out
+}
+
+/// Parses "四", "十六", "二十", "五十二" or plain ASCII digits.
+fn parse_cjk_number(s: &str) -> Option<u32> {
+    if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+        return s.parse().ok();
+    }
+    let digit = |c: char| CJK_DIGITS.iter().position(|&d| d == c).map(|i| i as u32);
+    let chars: Vec<char> = s.chars().collect();
+    match chars.as_slice() {
+        ['十'] => Some(10),
+        [d] => digit(*d),
+        ['十', u] => Some(10 + digit(*u)?),
+        [t, '十'] => Some(10 * digit(*t)?),
+        [t, '十', u] => Some(10 * digit(*t)? + digit(*u)?),
+        _ => None,
+    }
+}
@@ -0,0 +1,527 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use crate::coords::{gtp_column, point_to_gtp};
+use crate::markup::node_markup;
+use crate::{Board, Game, MarkupShape, Move, NodeMarkup, Point, Property, SgfNode, StoneColor};
+
+/// Inclusive rectangle of board points, (0, 0) being the upper-left corner.
+#[derive(uniffi::Record, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiagramRegion {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+}
+
+/// Move numbers to print, counted along the current path as `MN` and the moves define them.
+#[derive(uniffi::Record, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MoveRange {
+    pub first: u32,
+    pub last: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum StoneStyle {
+    /// Solid stones with an outline, as in printed books.
+    Flat,
+    /// Stones with a highlight, as on screen.
+    Shaded,
+}
+
+/// Colors as CSS values, e.g. "#dcb35c".
+#[derive(uniffi::Record, Clone, Debug, PartialEq)]
+pub struct DiagramTheme {
+    pub board: String,
+    pub line: String,
+    pub black_stone: String,
+    pub white_stone: String,
+    /// Outline of both colors of stones.
+    pub stone_outline: String,
+    /// Numbers and markup on black stones.
+    pub on_black: String,
+    /// Numbers and markup on white stones and empty points.
+    pub on_white: String,
+    pub coordinates: String,
+    pub font_family: String,
+}
+
+#[derive(uniffi::Record, Clone, Debug, PartialEq)]
+pub struct DiagramOptions {
+    /// Part of the board to draw; `None` draws all of it.
+    pub region: Option<DiagramRegion>,
+    pub coordinates: bool,
+    /// Moves to number. The diagram then shows the position before the first of them,
+    /// each move as a numbered stone, and "N at M" notes for moves on points already used.
+    pub move_range: Option<MoveRange>,
+    /// Draws the current node's markup.
+    pub markup: bool,
+    /// Circles the last move when no moves are numbered as a figure.
+    pub mark_last_move: bool,
+    pub stone_style: StoneStyle,
+    pub theme: DiagramTheme,
+    /// Distance between lines, in SVG units.
+    pub cell_size: f64,
+}
+
+impl Default for DiagramTheme {
+    fn default() -> Self {
+        wood_diagram_theme()
+    }
+}
+
+impl Default for DiagramOptions {
+    fn default() -> Self {
+        DiagramOptions {
+            region: None,
+            coordinates: true,
+            move_range: None,
+            markup: true,
+            mark_last_move: true,
+            stone_style: StoneStyle::Shaded,
+            theme: DiagramTheme::default(),
+            cell_size: 24.0,
+        }
+    }
+}
+
+/// Board colors as in the app.
+#[uniffi::export]
+pub fn wood_diagram_theme() -> DiagramTheme {
+    DiagramTheme {
+        board: "#dcb35c".into(),
+        line: "#3a2a10".into(),
+        black_stone: "#1a1a1a".into(),
+        white_stone: "#f4f4f0".into(),
+        stone_outline: "#202020".into(),
+        on_black: "#ffffff".into(),
+        on_white: "#000000".into(),
+        coordinates: "#3a2a10".into(),
+        font_family: "Helvetica, Arial, sans-serif".into(),
+    }
+}
+
+/// Black on white, for printed study notes.
+#[uniffi::export]
+pub fn print_diagram_theme() -> DiagramTheme {
+    DiagramTheme {
+        board: "#ffffff".into(),
+        line: "#000000".into(),
+        black_stone: "#000000".into(),
+        white_stone: "#ffffff".into(),
+        stone_outline: "#000000".into(),
+        on_black: "#ffffff".into(),
+        on_white: "#000000".into(),
+        coordinates: "#000000".into(),
+        font_family: "Times New Roman, serif".into(),
+    }
+}
+
+#[uniffi::export]
+pub fn default_diagram_options() -> DiagramOptions {
+    DiagramOptions::default()
+}
+
+#[uniffi::export]
+impl Board {
+    /// Draws the stones as an SVG document; markup and move numbers need a `Game`.
+    pub fn to_svg(&self, options: DiagramOptions) -> String {
+        Diagram::empty(self).to_svg(&options)
+    }
+}
+
+#[uniffi::export]
+impl Game {
+    /// Draws the current position, or the moves of `options.move_range`, as an SVG document.
+    pub fn to_svg(&self, options: DiagramOptions) -> String {
+        let path = self.state.lock().unwrap().path();
+        self.diagram(&path, &options, Numbering::Figure, |diagram| diagram.to_svg(&options))
+    }
+}
+
+/// How `Game::diagram` shows the moves of `DiagramOptions::move_range`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Numbering {
+    /// As in a printed figure: the position before the range with every move numbered on top.
+    Figure,
+    /// A kifu figure of the last `moves` moves of the path, drawn like `Figure` whatever
+    /// `move_range` says. `restart` numbers them from 1.
+    Kifu { moves: u32, restart: bool },
+    /// Numbers on the stones still on the board at the last node.
+    OnBoard,
+}
+
+impl Game {
+    /// Collects what `options` asks to draw at the last node of `path`, a line from the root,
+    /// and hands it to `draw`. The current node is left alone.
+    pub(crate) fn diagram<T>(
+        &self,
+        path: &[Arc<SgfNode>],
+        options: &DiagramOptions,
+        numbering: Numbering,
+        draw: impl FnOnce(&Diagram) -> T,
+    ) -> T {
+        let mut state = self.state.lock().unwrap();
+        let (width, height) = (state.width, state.height);
+        let (last, history) = path.split_last().expect("a path holds at least the root");
+        let markup = options.markup.then(|| node_markup(last, history, width, height));
+        let position = state.board_at(path);
+
+        // Move number and move of every node that plays one
+        let mut number = 0;
+        let mut moves = Vec::new();
+        for (i, node) in path.iter().enumerate() {
+            if let Some((n, color, mv)) = numbered_move(node, width, height, number) {
+                number = n;
+                moves.push((i, number, color, mv));
+            }
+        }
+
+        let in_range: Vec<_> = match (numbering, options.move_range) {
+            (Numbering::Kifu { moves: count, .. }, _) => moves[moves.len().saturating_sub(count as usize)..].iter().collect(),
+            (_, Some(range)) => moves.iter().filter(|m| (range.first..=range.last).contains(&m.1)).collect(),
+            (_, None) => vec![],
+        };
+        // A figure starts from the position before its first move
+        let start = match in_range.first() {
+            Some(&&(first, ..)) if numbering != Numbering::OnBoard => match first {
+                0 => Board::empty(width, height, state.rules),
+                _ => state.board_at(&path[..first]),
+            },
+            _ => position.clone(),
+        };
+        drop(state);
+
+        let mut diagram = Diagram::empty(&start);
+        diagram.markup = markup;
+        if in_range.is_empty() || numbering == Numbering::OnBoard {
+            diagram.last_move = moves.last().and_then(|&(i, _, _, mv)| match mv {
+                Move::Play { point } if i == path.len() - 1 && options.mark_last_move => Some(point),
+                _ => None,
+            });
+        }
+        let restart = matches!(numbering, Numbering::Kifu { restart: true, .. });
+        let in_range = in_range
+            .iter()
+            .enumerate()
+            .map(|(k, &&(_, number, color, mv))| (if restart { k as u32 + 1 } else { number }, color, mv));
+        match numbering {
+            Numbering::Figure | Numbering::Kifu { .. } => diagram.number_moves(in_range),
+            Numbering::OnBoard => {
+                // The last move on a point is the one whose stone can still be there
+                for (number, color, mv) in in_range {
+                    if let Move::Play { point } = mv {
+                        diagram.numbered.retain(|(p, ..)| *p != point);
+                        if position.get_stone(point.x, point.y) == Some(color) {
+                            diagram.numbered.push((point, color, number));
+                        }
+                    }
+                }
+            }
+        }
+        draw(&diagram)
+    }
+}
+
+/// The move `node` plays with its number: `MN` if given, else one after `previous`.
+pub(crate) fn numbered_move(node: &SgfNode, width: u32, height: u32, previous: u32) -> Option<(u32, StoneColor, Move)> {
+    let props = node.get_typed_properties(width, height);
+    let (color, mv) = props.iter().find_map(|p| match p {
+        Property::Move { color, mv } => Some((*color, *mv)),
+        _ => None,
+    })?;
+    let number = props
+        .iter()
+        .find_map(|p| match p {
+            Property::MoveNumber { number } => u32::try_from(*number).ok(),
+            _ => None,
+        })
+        .unwrap_or(previous + 1);
+    Some((number, color, mv))
+}
+
+/// Everything a diagram shows, independent of the output format.
+pub(crate) struct Diagram<'a> {
+    pub board: &'a Board,
+    /// Stones added on top of `board`, with their move numbers.
+    pub numbered: Vec<(Point, StoneColor, u32)>,
+    pub markup: Option<NodeMarkup>,
+    pub last_move: Option<Point>,
+    /// "N at M" and pass notes, in move order.
+    pub notes: Vec<String>,
+}
+
+impl<'a> Diagram<'a> {
+    pub fn empty(board: &'a Board) -> Self {
+        Diagram { board, numbered: vec![], markup: None, last_move: None, notes: vec![] }
+    }
+
+    /// Places numbered stones; moves on points already used become notes, grouped by the
+    /// stone they refer to as in "12, 15 at 6".
+    fn number_moves(&mut self, moves: impl Iterator<Item = (u32, StoneColor, Move)>) {
+        // Target of each "at" note and the note's index
+        let mut repeats: Vec<(String, usize)> = Vec::new();
+        let height = self.board.get_height();
+        for (number, color, mv) in moves {
+            let point = match mv {
+                Move::Play { point } => point,
+                Move::Pass => {
+                    self.notes.push(format!("{}: pass", number));
+                    continue;
+                }
+            };
+            let target = match self.numbered.iter().find(|(p, ..)| *p == point) {
+                Some((.., n)) => n.to_string(),
+                None if self.board.get_stone(point.x, point.y).is_some() => point_to_gtp(point.x, point.y, height),
+                None => {
+                    self.numbered.push((point, color, number));
+                    continue;
+                }
+            };
+            match repeats.iter().find(|(t, _)| *t == target) {
+                Some(&(_, i)) => {
+                    let (numbers, at) = self.notes[i].split_once(" at ").unwrap_or_default();
+                    self.notes[i] = format!("{}, {} at {}", numbers, number, at);
+                }
+                None => {
+                    repeats.push((target.clone(), self.notes.len()));
+                    self.notes.push(format!("{} at {}", number, target));
+                }
+            }
+        }
+    }
+
+    /// Stone at a point, numbered stones first.
+    pub fn stone(&self, point: Point) -> Option<(StoneColor, Option<u32>)> {
+        self.numbered
+            .iter()
+            .find(|(p, ..)| *p == point)
+            .map(|&(_, color, number)| (color, Some(number)))
+            .or_else(|| self.board.get_stone(point.x, point.y).map(|c| (c, None)))
+    }
+
+    /// The region to draw, clamped to the board.
+    pub fn region(&self, options: &DiagramOptions) -> DiagramRegion {
+        let (w, h) = (self.board.get_width(), self.board.get_height());
+        let r = options.region.unwrap_or(DiagramRegion { left: 0, top: 0, right: w - 1, bottom: h - 1 });
+        let (right, bottom) = (r.right.min(w - 1), r.bottom.min(h - 1));
+        DiagramRegion { left: r.left.min(right), top: r.top.min(bottom), right, bottom }
+    }
+
+    pub fn to_svg(&self, options: &DiagramOptions) -> String {
+        self.to_svg_placed(options, 0.0, 0.0, 1.0)
+    }
+
+    /// An `svg` element that can also be nested in another document, at (`x`, `y`) and
+    /// `scale` times the natural size given by `Layout`.
+    pub fn to_svg_placed(&self, options: &DiagramOptions, x: f64, y: f64, scale: f64) -> String {
+        let layout = Layout::new(self, options);
+        let theme = &options.theme;
+        let cell = layout.cell;
+        let r = layout.region;
+        let (w, h) = (self.board.get_width(), self.board.get_height());
+        let mut svg = String::new();
+
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" viewBox="0 0 {:.1} {:.1}" font-family="{}">"#,
+            x,
+            y,
+            layout.width * scale,
+            layout.height * scale,
+            layout.width,
+            layout.height,
+            escape(&theme.font_family)
+        );
+        if options.stone_style == StoneStyle::Shaded {
+            let _ = write!(
+                svg,
+                r##"<defs><radialGradient id="black" cx="35%" cy="35%" r="65%"><stop offset="0" stop-color="#666"/><stop offset="1" stop-color="{}"/></radialGradient><radialGradient id="white" cx="35%" cy="35%" r="65%"><stop offset="0" stop-color="#fff"/><stop offset="1" stop-color="{}"/></radialGradient></defs>"##,
+                theme.black_stone, theme.white_stone
+            );
+        }
+        let _ = write!(svg, r#"<rect width="100%" height="100%" fill="{}"/>"#, theme.board);
+
+        // Lines run on past the region where the board continues
+        let extend = |at_edge: bool| if at_edge { 0.0 } else { cell / 2.0 };
+        let (x0, x1) = (layout.x(r.left) - extend(r.left == 0), layout.x(r.right) + extend(r.right == w - 1));
+        let (y0, y1) = (layout.y(r.top) - extend(r.top == 0), layout.y(r.bottom) + extend(r.bottom == h - 1));
+        let _ = write!(svg, r#"<g stroke="{}" stroke-linecap="square">"#, theme.line);
+        for x in r.left..=r.right {
+            let width = if x == 0 || x == w - 1 { 2.0 } else { 1.0 };
+            let _ = write!(svg, r#"<line x1="{0:.1}" y1="{1:.1}" x2="{0:.1}" y2="{2:.1}" stroke-width="{3}"/>"#, layout.x(x), y0, y1, width);
+        }
+        for y in r.top..=r.bottom {
+            let width = if y == 0 || y == h - 1 { 2.0 } else { 1.0 };
+            let _ = write!(svg, r#"<line x1="{1:.1}" y1="{0:.1}" x2="{2:.1}" y2="{0:.1}" stroke-width="{3}"/>"#, layout.y(y), x0, x1, width);
+        }
+        svg.push_str("</g>");
+        for p in star_points(w, h).into_iter().filter(|&p| layout.contains(p)) {
+            let _ = write!(svg, r#"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}"/>"#, layout.x(p.x), layout.y(p.y), cell * 0.1, theme.line);
+        }
+
+        if options.coordinates {
+            let size = cell * 0.4;
+            let _ = write!(svg, r#"<g fill="{}" font-size="{:.1}" text-anchor="middle" dominant-baseline="central">"#, theme.coordinates, size);
+            for x in r.left..=r.right {
+                let _ = write!(svg, r#"<text x="{:.1}" y="{:.1}">{}</text>"#, layout.x(x), layout.y(r.top) - cell * 0.85, gtp_column(x));
+            }
+            for y in r.top..=r.bottom {
+                let _ = write!(svg, r#"<text x="{:.1}" y="{:.1}">{}</text>"#, layout.x(r.left) - cell * 0.85, layout.y(y), h - y);
+            }
+            svg.push_str("</g>");
+        }
+
+        let markup = self.markup.clone().unwrap_or_default();
+        let radius = cell * 0.48;
+        for y in r.top..=r.bottom {
+            for x in r.left..=r.right {
+                let point = Point { x, y };
+                let (cx, cy) = (layout.x(x), layout.y(y));
+                let Some((color, number)) = self.stone(point) else { continue };
+                let fill = match (options.stone_style, color) {
+                    (StoneStyle::Shaded, StoneColor::Black) => "url(#black)".to_string(),
+                    (StoneStyle::Shaded, StoneColor::White) => "url(#white)".to_string(),
+                    (StoneStyle::Flat, StoneColor::Black) => theme.black_stone.clone(),
+                    (StoneStyle::Flat, StoneColor::White) => theme.white_stone.clone(),
+                };
+                let _ = write!(svg, r#"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}" stroke="{}" stroke-width="{:.2}"/>"#, cx, cy, radius, fill, theme.stone_outline, cell / 24.0);
+                if let Some(number) = number {
+                    let text = number.to_string();
+                    // Keep three-digit numbers inside the stone
+                    let size = cell * if text.len() > 2 { 0.38 } else { 0.5 };
+                    let _ = write!(svg, r#"<text x="{:.1}" y="{:.1}" font-size="{:.1}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#, cx, cy, size, contrast(theme, Some(color)), text);
+                }
+            }
+        }
+        if let Some(point) = self.last_move.filter(|&p| layout.contains(p)) {
+            let stone = self.stone(point);
+            let color = contrast(theme, stone.map(|(c, _)| c));
+            // Around the number on a numbered stone, in place of it otherwise
+            let radius = cell * if stone.is_some_and(|(_, n)| n.is_some()) { 0.38 } else { 0.22 };
+            let _ = write!(svg, r#"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="none" stroke="{}" stroke-width="{:.2}"/>"#, layout.x(point.x), layout.y(point.y), radius, color, cell / 14.0);
+        }
+
+        for p in markup.dimmed.iter().filter(|&&p| layout.contains(p)) {
+            let _ = write!(svg, r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}" fill-opacity="0.6"/>"#, layout.x(p.x) - cell / 2.0, layout.y(p.y) - cell / 2.0, cell, cell, theme.board);
+        }
+        for mark in markup.marks.iter().filter(|m| layout.contains(m.point)) {
+            let color = contrast(theme, self.stone(mark.point).map(|(c, _)| c));
+            let (cx, cy, s) = (layout.x(mark.point.x), layout.y(mark.point.y), cell * 0.25);
+            let stroke = format!(r#"fill="none" stroke="{}" stroke-width="{:.2}""#, color, cell / 14.0);
+            let _ = match mark.shape {
+                MarkupShape::Circle => write!(svg, r#"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" {}/>"#, cx, cy, s, stroke),
+                MarkupShape::Cross => write!(svg, r#"<path d="M{:.1} {:.1}l{:.1} {:.1}m0 {:.1}l{:.1} {:.1}" {}/>"#, cx - s, cy - s, 2.0 * s, 2.0 * s, -2.0 * s, -2.0 * s, 2.0 * s, stroke),
+                MarkupShape::Square => write!(svg, r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" {}/>"#, cx - s, cy - s, 2.0 * s, 2.0 * s, stroke),
+                MarkupShape::Triangle => write!(svg, r#"<path d="M{:.1} {:.1}L{:.1} {:.1}L{:.1} {:.1}Z" {}/>"#, cx, cy - s * 1.2, cx + s * 1.1, cy + s * 0.7, cx - s * 1.1, cy + s * 0.7, stroke),
+                MarkupShape::Selected => write!(svg, r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}" fill-opacity="0.5"/>"#, cx - s, cy - s, 2.0 * s, 2.0 * s, color),
+                MarkupShape::Dimmed => Ok(()),
+            };
+        }
+        for label in markup.labels.iter().filter(|l| layout.contains(l.point)) {
+            let (cx, cy) = (layout.x(label.point.x), layout.y(label.point.y));
+            let stone = self.stone(label.point).map(|(c, _)| c);
+            if stone.is_none() {
+                // Clear the lines behind the text
+                let _ = write!(svg, r#"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}"/>"#, cx, cy, cell * 0.35, theme.board);
+            }
+            let size = cell * if label.text.chars().count() > 2 { 0.38 } else { 0.5 };
+            let _ = write!(svg, r#"<text x="{:.1}" y="{:.1}" font-size="{:.1}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#, cx, cy, size, contrast(theme, stone), escape(&label.text));
+        }
+        for (segments, arrow) in [(&markup.lines, false), (&markup.arrows, true)] {
+            for s in segments.iter().filter(|s| layout.contains(s.from) && layout.contains(s.to)) {
+                let (fx, fy, tx, ty) = (layout.x(s.from.x), layout.y(s.from.y), layout.x(s.to.x), layout.y(s.to.y));
+                let _ = write!(svg, r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="{:.2}"/>"#, fx, fy, tx, ty, theme.on_white, cell / 12.0);
+                let length = ((tx - fx).powi(2) + (ty - fy).powi(2)).sqrt();
+                if arrow && length > 0.0 {
+                    let (ux, uy) = ((tx - fx) / length, (ty - fy) / length);
+                    let (bx, by) = (tx - ux * cell * 0.35, ty - uy * cell * 0.35);
+                    let (nx, ny) = (-uy * cell * 0.15, ux * cell * 0.15);
+                    let _ = write!(svg, r#"<path d="M{:.1} {:.1}L{:.1} {:.1}L{:.1} {:.1}Z" fill="{}"/>"#, tx, ty, bx + nx, by + ny, bx - nx, by - ny, theme.on_white);
+                }
+            }
+        }
+
+        for (i, note) in self.notes.iter().enumerate() {
+            let _ = write!(svg, r#"<text x="{:.1}" y="{:.1}" font-size="{:.1}" fill="{}">{}</text>"#, layout.margin, layout.notes_top + (i as f64 + 0.8) * layout.note_height, cell * 0.45, theme.coordinates, escape(note));
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+/// Positions of the diagram's parts, in SVG units.
+pub(crate) struct Layout {
+    pub region: DiagramRegion,
+    pub cell: f64,
+    pub margin: f64,
+    pub width: f64,
+    pub height: f64,
+    /// Top of the notes below the board, each `note_height` tall.
+    pub notes_top: f64,
+    pub note_height: f64,
+}
+
+impl Layout {
+    pub fn new(diagram: &Diagram, options: &DiagramOptions) -> Self {
+        let region = diagram.region(options);
+        let cell = options.cell_size.max(1.0);
+        let margin = cell * if options.coordinates { 1.4 } else { 0.7 };
+        let width = (region.right - region.left) as f64 * cell + 2.0 * margin;
+        let board_height = (region.bottom - region.top) as f64 * cell + 2.0 * margin;
+        let note_height = cell * 0.65;
+        let notes = diagram.notes.len() as f64 * note_height;
+        Layout {
+            region,
+            cell,
+            margin,
+            width,
+            height: board_height + notes,
+            notes_top: board_height - margin / 2.0,
+            note_height,
+        }
+    }
+
+    pub fn x(&self, x: u32) -> f64 {
+        self.margin + (x - self.region.left) as f64 * self.cell
+    }
+
+    pub fn y(&self, y: u32) -> f64 {
+        self.margin + (y - self.region.top) as f64 * self.cell
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        (self.region.left..=self.region.right).contains(&p.x) && (self.region.top..=self.region.bottom).contains(&p.y)
+    }
+}
+
+/// Text color on a stone of `color`, or on the empty board.
+fn contrast(theme: &DiagramTheme, color: Option<StoneColor>) -> &str {
+    match color {
+        Some(StoneColor::Black) => &theme.on_black,
+        _ => &theme.on_white,
+    }
+}
+
+/// Hoshi: the corner points (third line below 13x13, fourth from 13x13), the center of
+/// odd boards, and the sides of odd boards from 15x15.
+pub(crate) fn star_points(width: u32, height: u32) -> Vec<Point> {
+    let lines = |size: u32| -> Vec<u32> {
+        let edge = if size >= 13 { 3 } else { 2 };
+        match size {
+            0..=6 => vec![],
+            _ if size % 2 == 1 => vec![edge, size / 2, size - 1 - edge],
+            _ => vec![edge, size - 1 - edge],
+        }
+    };
+    let sides = width >= 15 && height >= 15;
+    let (xs, ys) = (lines(width), lines(height));
+    xs.iter()
+        .flat_map(|&x| ys.iter().map(move |&y| Point { x, y }))
+        .filter(|p| sides || (p.x == width / 2 && width % 2 == 1) == (p.y == height / 2 && height % 2 == 1))
+        .collect()
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use encoding_rs::{Encoding, BIG5, EUC_KR, GBK, SHIFT_JIS, UTF_8};
+
+use crate::collection::SgfCollection;
+use crate::{parse_game_trees, Game, SgfError, SgfNode, SgfTree};
+
+/// Encodings tried when a file has no usable `CA`, in order of preference on a tie.
+const CANDIDATES: [&Encoding; 4] = [GBK, BIG5, SHIFT_JIS, EUC_KR];
+
+// Frequent characters of each language, including common go vocabulary. Text decoded with
+// the wrong legacy encoding rarely hits them, which is what tells the encodings apart.
+const SIMPLIFIED: &str = "的一是不了在人有我他这个们中来上大为和国地到以说时要就出会可也你对生能而子那得于着下自之年过发后作里用道行所然家种事成方多经么去法学如都同现当没动面起看定天分还进好小部其些主样理心本前开但因只从想实日者意无力与长把机十第公此已工使情明性知全三又关点正业外将两高间由问很最重并物手应向头文体黑白棋局胜负目半盘段届杯赛冠军决战贴劫打吃断连眼活死角边星位围选九八七六五四二李王张刘陈杨赵黄周吴";
+const TRADITIONAL: &str = "的一是不了在人有我他這個們中來上大為和國地到以說時要就出會可也你對生能而子那得於著下自之年過發後作裡用道行所然家種事成方多經麼去法學如都同現當沒動面起看定天分還進好小部其些主樣理心本前開但因只從想實日者意無力與長把機十第公此已工使情明性知全三又關點正業外將兩高間由問很最重並物手應向頭文體黑白棋局勝負目半盤段屆杯賽冠軍決戰貼劫打吃斷連眼活死角邊星位圍選九八七六五四二李王張劉陳楊趙黃周吳林";
+const JAPANESE_KANJI: &str = "日本人年大中一出目白黒勝負碁段棋聖名人因坊王座天元十局戦対手番先中押半目時間回期杯予選決準優本田山川井藤木村小高橋佐渡辺伊加";
+const KOREAN: &str = "이의다는에을가하고지기로서한도를사대어국시수자일정으리나인적과전상보요게해있들주구만장제아면부동니것라마소신위선원성비우단김박최흑백승반집패불계결바둑기사국수명인왕위천원배대회단";
+
+/// Text of an SGF file decoded from bytes.
+#[derive(uniffi::Record, Clone, Debug)]
+pub struct DecodedSgf {
+    pub text: String,
+    /// Name of the encoding used, e.g. "GBK" or "Shift_JIS".
+    pub encoding: String,
+    /// The file's `CA` value, if it has one.
+    pub declared: Option<String>,
+}
+
+/// Decodes an SGF file: a byte order mark wins, then valid UTF-8, then a `CA` the bytes
+/// actually decode with, then whichever of GBK, Big5, Shift_JIS and EUC-KR reads most like text.
+#[uniffi::export]
+pub fn decode_sgf_bytes(bytes: Vec<u8>) -> DecodedSgf {
+    let declared = declared_charset(&bytes);
+    if let Some((encoding, _)) = Encoding::for_bom(&bytes) {
+        let (text, _) = encoding.decode_with_bom_removal(&bytes);
+        return DecodedSgf { text: text.into_owned(), encoding: encoding.name().to_string(), declared };
+    }
+
+    // Valid UTF-8 with non-ASCII text is almost never a legacy encoding, whatever CA says
+    let declared_encoding = declared.as_deref().and_then(charset_encoding);
+    let encoding = Some(UTF_8)
+        .filter(|e| decodes_cleanly(e, &bytes))
+        .or_else(|| declared_encoding.filter(|e| decodes_cleanly(e, &bytes)))
+        .or_else(|| {
+            let scored = CANDIDATES
+                .iter()
+                .filter(|e| decodes_cleanly(e, &bytes))
+                .map(|e| (*e, text_score(e, &bytes)));
+            // max_by_key keeps the last maximum, so walk the candidates in reverse
+            scored.rev().max_by_key(|&(_, score)| score).map(|(e, _)| e)
+        })
+        .unwrap_or(declared_encoding.unwrap_or(UTF_8));
+
+    let (text, _) = encoding.decode_without_bom_handling(&bytes);
+    DecodedSgf { text: text.into_owned(), encoding: encoding.name().to_string(), declared }
+}
+
+/// Parses the first game of an SGF file given as raw bytes in any supported encoding.
+#[uniffi::export]
+pub fn parse_sgf_bytes(bytes: Vec<u8>) -> Result<Arc<SgfTree>, SgfError> {
+    Ok(Arc::new(SgfTree { root: game_trees(bytes)?.swap_remove(0) }))
+}
+
+#[uniffi::export]
+impl Game {
+    /// Loads the first game of an SGF file given as raw bytes; see `decode_sgf_bytes`.
+    #[uniffi::constructor]
+    pub fn from_sgf_bytes(bytes: Vec<u8>) -> Result<Arc<Self>, SgfError> {
+        Ok(Self::from_root(game_trees(bytes)?.swap_remove(0)))
+    }
+}
+
+#[uniffi::export]
+impl SgfCollection {
+    /// Loads every game of an SGF file given as raw bytes; see `decode_sgf_bytes`.
+    #[uniffi::constructor]
+    pub fn from_sgf_bytes(bytes: Vec<u8>) -> Result<Arc<Self>, SgfError> {
+        Ok(Self::from_roots(game_trees(bytes)?))
+    }
+}
+
+/// Every game tree of a file given as raw bytes; saving writes `CA[UTF-8]` unless the file
+/// was UTF-8 without a `CA`.
+fn game_trees(bytes: Vec<u8>) -> Result<Vec<Arc<SgfNode>>, SgfError> {
+    let decoded = decode_sgf_bytes(bytes);
+    parse_game_trees(decoded.text, decoded.encoding != UTF_8.name())
+}
+
+/// Value of the first `CA` property. Every supported encoding keeps ASCII bytes as ASCII,
+/// so the identifier and brackets can be found before decoding.
+fn declared_charset(bytes: &[u8]) -> Option<String> {
+    let start = bytes
+        .windows(3)
+        .enumerate()
+        .find(|&(i, w)| w == b"CA[" && (i == 0 || !bytes[i - 1].is_ascii_uppercase()))
+        .map(|(i, _)| i + 3)?;
+    let len = bytes[start..].iter().position(|&b| b == b']')?;
+    let value = std::str::from_utf8(&bytes[start..start + len]).ok()?.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Encoding for a `CA` value, including the code page names some editors write.
+fn charset_encoding(charset: &str) -> Option<&'static Encoding> {
+    match charset.to_ascii_lowercase().as_str() {
+        "cp936" | "ms936" | "windows-936" => Some(GBK),
+        "cp950" | "ms950" | "windows-950" => Some(BIG5),
+        "cp932" | "ms932" | "windows-932" | "sjis" | "shift-jis" => Some(SHIFT_JIS),
+        "cp949" | "ms949" | "windows-949" | "uhc" => Some(EUC_KR),
+        _ => Encoding::for_label(charset.as_bytes()),
+    }
+}
+
+fn decodes_cleanly(encoding: &'static Encoding, bytes: &[u8]) -> bool {
+    encoding.decode_without_bom_handling_and_without_replacement(bytes).is_some()
+}
+
+/// How much the bytes decoded with `encoding` look like text in that encoding's language.
+fn text_score(encoding: &'static Encoding, bytes: &[u8]) -> i64 {
+    let (text, _) = encoding.decode_without_bom_handling(bytes);
+    let frequent = |c: char| {
+        if encoding == GBK {
+            SIMPLIFIED.contains(c)
+        } else if encoding == BIG5 {
+            TRADITIONAL.contains(c)
+        } else if encoding == SHIFT_JIS {
+            matches!(c, '\u{3041}'..='\u{30ff}') || JAPANESE_KANJI.contains(c)
+        } else {
+            KOREAN.contains(c)
+        }
+    };
+    text.chars()
+        .map(|c| match c {
+            // Half-width katakana is what Chinese and Korean bytes turn into as Shift_JIS
+            '\u{ff61}'..='\u{ff9f}' | '\u{e000}'..='\u{f8ff}' => -2,
+            _ if frequent(c) => 1,
+            _ => 0,
+        })
+        .sum()
+}
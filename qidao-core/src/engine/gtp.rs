@@ -52,10 +52,10 @@ impl GtpClient {
             response.push_str(&line);
         }
         
-        if response.starts_with('=') {
-            Ok(response[1..].trim().to_string())
-        } else if response.starts_with('?') {
-            Err(anyhow!("GTP Error: {}", response[1..].trim()))
+        if let Some(rest) = response.strip_prefix('=') {
+            Ok(rest.trim().to_string())
+        } else if let Some(rest) = response.strip_prefix('?') {
+            Err(anyhow!("GTP Error: {}", rest.trim()))
         } else {
             Ok(response.trim().to_string())
         }
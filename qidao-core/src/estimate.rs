@@ -0,0 +1,136 @@
+use crate::rules::ScoringType;
+use crate::{Board, Game, StoneColor};
+
+// Bouzy's dilation/erosion passes, as popularised by GNU Go: 5/21 marks territory,
+// 5/10 moyo and 4/0 the wider area of influence.
+const TERRITORY: (u32, u32) = (5, 21);
+const MOYO: (u32, u32) = (5, 10);
+const AREA: (u32, u32) = (4, 0);
+
+/// Engine-free position estimate.
+#[derive(uniffi::Record, Clone, Debug)]
+pub struct TerritoryEstimate {
+    /// One value per point, row by row from the top-left, in the layout of
+    /// `AnalysisResult::ownership`: 1.0 is Black's, -1.0 is White's.
+    pub ownership: Vec<f64>,
+    /// Empty points counted as territory by the 5/21 pass.
+    pub black_territory: u32,
+    pub white_territory: u32,
+    /// Estimated Black lead after komi (negative when White leads).
+    pub score_lead: f64,
+}
+
+#[uniffi::export]
+impl Board {
+    /// Estimates ownership and the score without an engine, counting area (stones plus territory).
+    pub fn estimate_territory(&self, komi: f64) -> TerritoryEstimate {
+        self.estimate(komi, ScoringType::Area)
+    }
+}
+
+#[uniffi::export]
+impl Game {
+    /// Estimates the current position with the game's komi and scoring rules.
+    pub fn estimate_territory(&self) -> TerritoryEstimate {
+        let komi = self.get_metadata().komi;
+        let scoring = self.get_rules().scoring;
+        self.get_board().estimate(komi, scoring)
+    }
+}
+
+impl Board {
+    fn estimate(&self, komi: f64, scoring: ScoringType) -> TerritoryEstimate {
+        let territory = self.bouzy(TERRITORY);
+        let moyo = self.bouzy(MOYO);
+        let area = self.bouzy(AREA);
+
+        let mut black_territory = 0;
+        let mut white_territory = 0;
+        let mut black_stones = 0;
+        let mut white_stones = 0;
+        let ownership = (0..self.grid.len())
+            .map(|i| match self.grid[i] {
+                Some(StoneColor::Black) => {
+                    black_stones += 1;
+                    1.0
+                }
+                Some(StoneColor::White) => {
+                    white_stones += 1;
+                    -1.0
+                }
+                None if territory[i] > 0 => {
+                    black_territory += 1;
+                    0.9
+                }
+                None if territory[i] < 0 => {
+                    white_territory += 1;
+                    -0.9
+                }
+                None => 0.5 * moyo[i].signum() as f64 + 0.2 * area[i].signum() as f64,
+            })
+            .collect();
+
+        let (black, white) = match scoring {
+            ScoringType::Area => (black_territory + black_stones, white_territory + white_stones),
+            ScoringType::Territory => (
+                black_territory + self.prisoners.black,
+                white_territory + self.prisoners.white,
+            ),
+        };
+        TerritoryEstimate {
+            ownership,
+            black_territory,
+            white_territory,
+            score_lead: black as f64 - white as f64 - komi,
+        }
+    }
+
+    /// Runs Bouzy's algorithm: stones start at ±128, then `dilations` rounds spread
+    /// influence into uncontested points and `erosions` rounds wear it away at the borders.
+    fn bouzy(&self, (dilations, erosions): (u32, u32)) -> Vec<i32> {
+        let mut values: Vec<i32> = self
+            .grid
+            .iter()
+            .map(|stone| match stone {
+                Some(StoneColor::Black) => 128,
+                Some(StoneColor::White) => -128,
+                None => 0,
+            })
+            .collect();
+        for _ in 0..dilations {
+            values = self.dilate(&values);
+        }
+        for _ in 0..erosions {
+            values = self.erode(&values);
+        }
+        values
+    }
+
+    fn dilate(&self, v: &[i32]) -> Vec<i32> {
+        (0..v.len())
+            .map(|i| {
+                if v[i] >= 0 && self.adjacent(i).all(|n| v[n] >= 0) {
+                    v[i] + self.adjacent(i).filter(|&n| v[n] > 0).count() as i32
+                } else if v[i] <= 0 && self.adjacent(i).all(|n| v[n] <= 0) {
+                    v[i] - self.adjacent(i).filter(|&n| v[n] < 0).count() as i32
+                } else {
+                    v[i]
+                }
+            })
+            .collect()
+    }
+
+    fn erode(&self, v: &[i32]) -> Vec<i32> {
+        (0..v.len())
+            .map(|i| {
+                if v[i] > 0 {
+                    (v[i] - self.adjacent(i).filter(|&n| v[n] <= 0).count() as i32).max(0)
+                } else if v[i] < 0 {
+                    (v[i] + self.adjacent(i).filter(|&n| v[n] >= 0).count() as i32).min(0)
+                } else {
+                    0
+                }
+            })
+            .collect()
+    }
+}
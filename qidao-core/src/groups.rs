@@ -0,0 +1,96 @@
+use crate::{Board, Point, StoneColor};
+
+/// A chain of connected stones and the empty points next to it.
+#[derive(uniffi::Record, Clone, Debug, PartialEq, Eq)]
+pub struct Group {
+    pub color: StoneColor,
+    /// Sorted top to bottom, then left to right.
+    pub stones: Vec<Point>,
+    /// Sorted top to bottom, then left to right.
+    pub liberties: Vec<Point>,
+}
+
+#[uniffi::export]
+impl Board {
+    /// The chain containing the stone at (x, y), or `None` for an empty point.
+    pub fn get_group(&self, x: u32, y: u32) -> Option<Group> {
+        self.get_stone(x, y)?;
+        Some(self.group_at((y * self.width + x) as usize))
+    }
+
+    /// Number of distinct liberties of the chain at (x, y); 0 for an empty point.
+    pub fn get_liberty_count(&self, x: u32, y: u32) -> u32 {
+        if self.get_stone(x, y).is_none() {
+            return 0;
+        }
+        self.liberties((y * self.width + x) as usize).len() as u32
+    }
+
+    /// Opponent chains touching the chain at (x, y).
+    pub fn get_adjacent_enemy_groups(&self, x: u32, y: u32) -> Vec<Group> {
+        let Some(color) = self.get_stone(x, y) else {
+            return vec![];
+        };
+        let mut heads: Vec<usize> = self
+            .chain((y * self.width + x) as usize)
+            .flat_map(|s| self.adjacent(s))
+            .filter(|&n| self.grid[n] == Some(color.opponent()))
+            .map(|n| self.chains.head(n))
+            .collect();
+        heads.sort_unstable();
+        heads.dedup();
+        sorted_groups(heads.into_iter().map(|h| self.group_at(h)).collect())
+    }
+
+    /// Chains with exactly one liberty; `None` returns those of both colors.
+    pub fn get_groups_in_atari(&self, color: Option<StoneColor>) -> Vec<Group> {
+        let groups = (0..self.grid.len())
+            .filter(|&i| match self.grid[i] {
+                Some(c) => color.is_none_or(|wanted| wanted == c) && self.chains.head(i) == i,
+                None => false,
+            })
+            .filter(|&i| self.liberties(i).len() == 1)
+            .map(|i| self.group_at(i))
+            .collect();
+        sorted_groups(groups)
+    }
+
+    /// Whether playing `color` at (x, y) would leave the played chain with a single liberty,
+    /// after any captures. Illegal moves are not self-atari.
+    pub fn is_self_atari(&self, x: u32, y: u32, color: StoneColor) -> bool {
+        match self.play(x, y, color) {
+            Ok(result) => result.board.get_liberty_count(x, y) == 1,
+            Err(_) => false,
+        }
+    }
+}
+
+impl Board {
+    pub(crate) fn group_at(&self, idx: usize) -> Group {
+        let mut stones: Vec<usize> = self.chain(idx).collect();
+        stones.sort_unstable();
+        Group {
+            color: self.grid[idx].expect("group_at needs a stone"),
+            stones: stones.into_iter().map(|i| self.point(i)).collect(),
+            liberties: self.liberties(idx).into_iter().map(|i| self.point(i)).collect(),
+        }
+    }
+
+    /// Distinct empty points next to the chain containing `idx`, in grid order.
+    pub(crate) fn liberties(&self, idx: usize) -> Vec<usize> {
+        let mut liberties: Vec<usize> = self
+            .chain(idx)
+            .flat_map(|s| self.adjacent(s))
+            .filter(|&n| self.grid[n].is_none())
+            .collect();
+        liberties.sort_unstable();
+        liberties.dedup();
+        liberties
+    }
+}
+
+/// Orders groups by their first stone, top to bottom and left to right.
+fn sorted_groups(mut groups: Vec<Group>) -> Vec<Group> {
+    groups.sort_by_key(|g| g.stones.first().map(|p| (p.y, p.x)));
+    groups
+}
@@ -0,0 +1,37 @@
+use crate::{Point, SgfError};
+
+/// Standard fixed handicap points, in the order defined by the GTP `fixed_handicap` command.
+///
+/// Odd boards of 9x9 and larger accept 2–9 stones; other boards of 7x7 and larger
+/// only accept the four corner points.
+#[uniffi::export]
+pub fn fixed_handicap_points(size: u32, count: u32) -> Result<Vec<Point>, SgfError> {
+    let max = if size >= 9 && size % 2 == 1 { 9 } else { 4 };
+    if size < 7 || !(2..=max).contains(&count) {
+        return Err(SgfError::ParseError {
+            message: format!("No fixed handicap of {} stones on {}x{}", count, size, size),
+        });
+    }
+
+    let lo = if size >= 13 { 3 } else { 2 };
+    let hi = size - 1 - lo;
+    let mid = size / 2;
+    let p = |x, y| Point { x, y };
+
+    let corners = [p(lo, hi), p(hi, lo), p(lo, lo), p(hi, hi)];
+    let left_right = [p(lo, mid), p(hi, mid)];
+    let top_bottom = [p(mid, hi), p(mid, lo)];
+    let center = p(mid, mid);
+
+    let mut points: Vec<Point> = corners.iter().take(count.min(4) as usize).copied().collect();
+    if count >= 6 {
+        points.extend(left_right);
+    }
+    if count >= 8 {
+        points.extend(top_bottom);
+    }
+    if count % 2 == 1 && count >= 5 {
+        points.push(center);
+    }
+    Ok(points)
+}
@@ -0,0 +1,485 @@
+use std::sync::{Arc, Mutex};
+
+use crate::coords::sgf_coord;
+use crate::{
+    board_size_value, decode_sgf_bytes, fixed_handicap_points, parse_sgf, Game, SgfError, SgfNode, SgfProperty,
+    SgfTree, StoneColor,
+};
+
+/// Game record formats `parse_kifu` reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum KifuFormat {
+    Sgf,
+    /// Tygem `.gib`.
+    Gib,
+    /// WBaduk / Oro `.ngf`.
+    Ngf,
+    /// PandaNet and Fox `.ugf` / `.ugi`.
+    Ugf,
+    /// KGS-era `.qgs`, opened by its content: the first SGF game tree in the file, or a
+    /// record in one of the other formats.
+    Qgs,
+}
+
+/// Format for a file extension, with or without the dot; `None` if it is not a kifu format.
+#[uniffi::export]
+pub fn kifu_format_for_extension(extension: String) -> Option<KifuFormat> {
+    match extension.trim_start_matches('.').to_ascii_lowercase().as_str() {
+        "sgf" => Some(KifuFormat::Sgf),
+        "gib" => Some(KifuFormat::Gib),
+        "ngf" => Some(KifuFormat::Ngf),
+        "ugf" | "ugi" => Some(KifuFormat::Ugf),
+        "qgs" => Some(KifuFormat::Qgs),
+        _ => None,
+    }
+}
+
+/// Guesses the format of a game record from its text.
+#[uniffi::export]
+pub fn detect_kifu_format(content: String) -> Option<KifuFormat> {
+    let text = content.trim_start_matches('\u{feff}').trim_start();
+    if text.starts_with('(') {
+        Some(KifuFormat::Sgf)
+    } else if text.contains("\\HS") || text.contains("\\[GAMEBLACKNAME=") {
+        Some(KifuFormat::Gib)
+    } else if text.to_ascii_lowercase().contains("[header]") {
+        Some(KifuFormat::Ugf)
+    } else if text.lines().nth(1).is_some_and(|l| l.trim().parse::<u32>().is_ok())
+        && text.lines().any(|l| l.trim_start().starts_with("PM"))
+    {
+        Some(KifuFormat::Ngf)
+    } else {
+        None
+    }
+}
+
+/// Reads a game record as an SGF tree; `None` detects the format from the text.
+#[uniffi::export]
+pub fn parse_kifu(content: String, format: Option<KifuFormat>) -> Result<Arc<SgfTree>, SgfError> {
+    let format = format.or_else(|| detect_kifu_format(content.clone())).ok_or_else(|| SgfError::ParseError {
+        message: "Unrecognized game record format".to_string(),
+    })?;
+    let record = match format {
+        KifuFormat::Sgf => return parse_sgf(content),
+        KifuFormat::Gib => read_gib(&content)?,
+        KifuFormat::Ngf => read_ngf(&content)?,
+        KifuFormat::Ugf => read_ugf(&content)?,
+        KifuFormat::Qgs => return read_qgs(&content),
+    };
+    Ok(Arc::new(SgfTree { root: record.into_tree() }))
+}
+
+/// Reads a game record given as raw bytes; Tygem and WBaduk files are usually EUC-KR.
+/// See `decode_sgf_bytes`.
+#[uniffi::export]
+pub fn parse_kifu_bytes(bytes: Vec<u8>, format: Option<KifuFormat>) -> Result<Arc<SgfTree>, SgfError> {
+    parse_kifu(decode_sgf_bytes(bytes).text, format)
+}
+
+#[uniffi::export]
+impl Game {
+    /// Opens a game record in any `KifuFormat`; `None` detects the format from the text.
+    #[uniffi::constructor]
+    pub fn from_kifu(content: String, format: Option<KifuFormat>) -> Result<Arc<Self>, SgfError> {
+        Ok(Self::from_root(parse_kifu(content, format)?.root()))
+    }
+
+    #[uniffi::constructor]
+    pub fn from_kifu_bytes(bytes: Vec<u8>, format: Option<KifuFormat>) -> Result<Arc<Self>, SgfError> {
+        Ok(Self::from_root(parse_kifu_bytes(bytes, format)?.root()))
+    }
+}
+
+/// What the importers read, before it becomes an SGF tree.
+struct Record {
+    size: u32,
+    /// Root properties besides the size and handicap stones, in SGF order.
+    info: Vec<(&'static str, String)>,
+    /// Handicap stones go on the standard points; none of the formats list them.
+    handicap: u32,
+    /// Moves in order; `None` is a pass.
+    moves: Vec<(StoneColor, Option<(u32, u32)>)>,
+}
+
+impl Record {
+    fn new(size: u32) -> Self {
+        Record { size, info: vec![], handicap: 0, moves: vec![] }
+    }
+
+    /// Adds a root property unless the value is blank.
+    fn set(&mut self, id: &'static str, value: impl Into<String>) {
+        let value = value.into().trim().to_string();
+        if !value.is_empty() {
+            self.info.retain(|(i, _)| *i != id);
+            self.info.push((id, value));
+        }
+    }
+
+    fn set_player(&mut self, color: StoneColor, text: &str) {
+        let (name, rank) = split_rank(text);
+        let (name_id, rank_id) = match color {
+            StoneColor::Black => ("PB", "BR"),
+            StoneColor::White => ("PW", "WR"),
+        };
+        self.set(name_id, name);
+        if let Some(rank) = rank {
+            self.set(rank_id, rank);
+        }
+    }
+
+    fn into_tree(self) -> Arc<SgfNode> {
+        let prop = |identifier: &str, values: Vec<String>| SgfProperty { identifier: identifier.to_string(), values };
+        let mut root = vec![
+            prop("GM", vec!["1".into()]),
+            prop("FF", vec!["4".into()]),
+            prop("CA", vec!["UTF-8".into()]),
+            prop("SZ", vec![board_size_value(self.size, self.size)]),
+        ];
+        root.extend(self.info.into_iter().map(|(id, value)| prop(id, vec![value])));
+
+        if self.handicap >= 2 {
+            root.push(prop("HA", vec![self.handicap.to_string()]));
+            if let Ok(points) = fixed_handicap_points(self.size, self.handicap) {
+                root.push(prop("AB", points.into_iter().map(|p| sgf_coord(p.x, p.y)).collect()));
+            }
+        }
+
+        let root = node(root);
+        let mut last = root.clone();
+        for (color, point) in self.moves {
+            let id = if color == StoneColor::Black { "B" } else { "W" };
+            let value = point
+                .filter(|&(x, y)| x < self.size && y < self.size)
+                .map(|(x, y)| sgf_coord(x, y))
+                .unwrap_or_default();
+            let child = node(vec![prop(id, vec![value])]);
+            last.children.lock().unwrap().push(child.clone());
+            last = child;
+        }
+        root
+    }
+}
+
+fn node(properties: Vec<SgfProperty>) -> Arc<SgfNode> {
+    Arc::new(SgfNode { properties: Mutex::new(properties), children: Mutex::new(vec![]), source: vec![] })
+}
+
+/// Tygem: `\[KEY=value\]` header lines between `\HS` and `\HE`, then one line per move
+/// between `\GS` and `\GE`, e.g. `STO 0 12 2 15 3` (move 12, White, column 15, row 3).
+fn read_gib(text: &str) -> Result<Record, SgfError> {
+    let header = |key: &str| {
+        let tag = format!("\\[{}=", key);
+        text.lines()
+            .find_map(|l| l.trim().strip_prefix(tag.as_str()))
+            .map(|v| v.trim_end().trim_end_matches("\\]").trim().to_string())
+    };
+    // GAMEINFOMAIN holds comma separated KEY:value pairs
+    let main_info = header("GAMEINFOMAIN").unwrap_or_default();
+    let info = |key: &str| {
+        main_info
+            .split(',')
+            .find_map(|pair| pair.strip_prefix(key).and_then(|v| v.strip_prefix(':')))
+            .map(str::trim)
+    };
+
+    let mut record = Record::new(19);
+    for (color, key) in [(StoneColor::Black, "BLACK"), (StoneColor::White, "WHITE")] {
+        let name = header(&format!("GAME{}NAME", key))
+            .or_else(|| header(&format!("GAME{}NICK", key)))
+            .unwrap_or_default();
+        record.set_player(color, &name);
+        let rank_id = if color == StoneColor::Black { "BR" } else { "WR" };
+        if !record.info.iter().any(|(id, _)| *id == rank_id) {
+            if let Some(level) = header(&format!("GAME{}LEVEL", key)).and_then(|l| l.parse::<i32>().ok()) {
+                record.set(rank_id, tygem_rank(level));
+            }
+        }
+    }
+
+    let komi = info("GONGJE").map(str::to_string).or_else(|| header("GAMEGONGJE"));
+    if let Some(komi) = komi.and_then(|k| k.parse::<f64>().ok()) {
+        record.set("KM", (komi / 10.0).to_string());
+    }
+    let score = info("ZIPSU").and_then(|z| z.parse::<f64>().ok()).map(|z| z / 10.0);
+    let result = match (info("GRLT"), score) {
+        (Some("0"), Some(score)) => format!("B+{}", score),
+        (Some("1"), Some(score)) => format!("W+{}", score),
+        (Some("3"), _) => "B+R".to_string(),
+        (Some("4"), _) => "W+R".to_string(),
+        (Some("7"), _) => "B+T".to_string(),
+        (Some("8"), _) => "W+T".to_string(),
+        _ => header("GAMERESULT").map(|r| result_from_text(&r)).unwrap_or_default(),
+    };
+    record.set("RE", result);
+    if let Some(date) = header("GAMEDATE").and_then(|d| date_from(&d)) {
+        record.set("DT", date);
+    }
+    record.set("EV", header("GAMENAME").unwrap_or_default());
+    record.set("PC", header("GAMEPLACE").unwrap_or_default());
+
+    let mut in_game = false;
+    for line in text.lines().map(str::trim) {
+        match line {
+            "\\GS" => in_game = true,
+            "\\GE" => in_game = false,
+            _ if in_game => {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let number = |i: usize| fields.get(i).and_then(|f| f.parse::<u32>().ok());
+                let color = |i: usize| match number(i) {
+                    Some(2) => StoneColor::White,
+                    _ => StoneColor::Black,
+                };
+                match fields.first() {
+                    Some(&"INI") => record.handicap = number(3).unwrap_or(0),
+                    Some(&"STO") => {
+                        let point = number(4).zip(number(5));
+                        record.moves.push((color(3), point));
+                    }
+                    Some(&"SKI") => {
+                        // A pass is by the side not to move after the previous one
+                        let color = match record.moves.last() {
+                            Some((StoneColor::Black, _)) => StoneColor::White,
+                            Some(_) => StoneColor::Black,
+                            None if record.handicap >= 2 => StoneColor::White,
+                            None => StoneColor::Black,
+                        };
+                        record.moves.push((color, None));
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if record.moves.is_empty() && !record.info.iter().any(|(id, _)| *id == "PB" || *id == "PW") {
+        return Err(SgfError::ParseError { message: "Not a Tygem GIB record".to_string() });
+    }
+    Ok(record)
+}
+
+/// Tygem levels count up from 30 kyu; 18 is 1 dan.
+fn tygem_rank(level: i32) -> String {
+    if level >= 18 {
+        format!("{}d", level - 17)
+    } else {
+        format!("{}k", 18 - level)
+    }
+}
+
+/// WBaduk: twelve header lines (name, size, white, black, place, handicap, unused, komi
+/// without its half point, date, time, result, move count), then `PM` move lines such as
+/// `PMABBEPAB`: move number, colour, then column and row letters starting at `B`.
+fn read_ngf(text: &str) -> Result<Record, SgfError> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    if lines.len() < 12 {
+        return Err(SgfError::ParseError { message: "NGF header is incomplete".to_string() });
+    }
+    let size = lines[1].parse::<u32>().ok().filter(|s| (2..=25).contains(s)).ok_or_else(|| {
+        SgfError::ParseError { message: format!("Invalid NGF board size: {}", lines[1]) }
+    })?;
+
+    let mut record = Record::new(size);
+    record.set("GN", lines[0]);
+    record.set_player(StoneColor::White, lines[2]);
+    record.set_player(StoneColor::Black, lines[3]);
+    record.set("PC", lines[4]);
+    record.handicap = lines[5].parse().unwrap_or(0);
+    if let Ok(komi) = lines[7].parse::<f64>() {
+        record.set("KM", (komi + 0.5).to_string());
+    }
+    if let Some(date) = date_from(lines[8]) {
+        record.set("DT", date);
+    }
+    record.set("RE", result_from_text(lines[10]));
+
+    let coord = |c: u8| (c as u32).checked_sub(b'B' as u32).filter(|&v| v < size);
+    for line in &lines[12..] {
+        let bytes = line.as_bytes();
+        if !line.starts_with("PM") || bytes.len() < 7 {
+            continue;
+        }
+        let color = if bytes[4] == b'W' { StoneColor::White } else { StoneColor::Black };
+        record.moves.push((color, coord(bytes[5]).zip(coord(bytes[6]))));
+    }
+    Ok(record)
+}
+
+/// PandaNet/Fox: INI-style `[Header]` keys, then `[Data]` lines such as `QD,B1,0`:
+/// column and row letters from `A` with rows counted from the bottom, then colour and
+/// move number.
+fn read_ugf(text: &str) -> Result<Record, SgfError> {
+    let mut section = String::new();
+    let mut header = Vec::new();
+    let mut data = Vec::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if line.starts_with('[') && line.ends_with(']') {
+            section = line[1..line.len() - 1].to_ascii_lowercase();
+        } else if section == "header" {
+            if let Some((key, value)) = line.split_once('=') {
+                header.push((key.trim().to_ascii_lowercase(), value.trim()));
+            }
+        } else if section == "data" {
+            data.push(line);
+        }
+    }
+    let field = |key: &str| header.iter().find(|(k, _)| k == key).map(|(_, v)| *v);
+    let parts = |key: &str| field(key).map(|v| v.split(',').map(str::trim).collect::<Vec<_>>()).unwrap_or_default();
+
+    let size = field("size").and_then(|s| s.parse::<u32>().ok()).filter(|s| (2..=25).contains(s)).unwrap_or(19);
+    let mut record = Record::new(size);
+    record.set("GN", field("title").unwrap_or_default());
+    record.set("PC", field("place").unwrap_or_default());
+    for (color, key) in [(StoneColor::Black, "playerb"), (StoneColor::White, "playerw")] {
+        let player = parts(key);
+        record.set_player(color, player.first().copied().unwrap_or_default());
+        if let Some(rank) = player.get(1).filter(|r| !r.is_empty()) {
+            let id = if color == StoneColor::Black { "BR" } else { "WR" };
+            record.set(id, normalize_rank(rank));
+        }
+    }
+    let handicap = parts("hdcp");
+    record.handicap = handicap.first().and_then(|h| h.parse().ok()).unwrap_or(0);
+    if let Some(komi) = handicap.get(1).and_then(|k| k.parse::<f64>().ok()) {
+        record.set("KM", komi.to_string());
+    }
+    if let Some(date) = field("date").and_then(date_from) {
+        record.set("DT", date);
+    }
+    let winner = parts("winner");
+    let result = match (winner.first().copied(), winner.get(1).copied()) {
+        (Some(w @ ("B" | "W")), how) => {
+            let how = match how {
+                Some("C" | "R") => "R".to_string(),
+                Some("T") => "T".to_string(),
+                Some(score) if score.parse::<f64>().is_ok_and(|s| s > 0.0) => score.to_string(),
+                _ => String::new(),
+            };
+            format!("{}+{}", w, how)
+        }
+        (Some("D" | "0"), _) => "0".to_string(),
+        _ => String::new(),
+    };
+    record.set("RE", result);
+
+    let coord = |c: u8| (c as u32).checked_sub(b'A' as u32).filter(|&v| v < size);
+    for line in data {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let (Some(point), Some(mover)) = (fields.first(), fields.get(1)) else { continue };
+        let color = match mover.chars().next() {
+            Some('B') => StoneColor::Black,
+            Some('W') => StoneColor::White,
+            _ => continue,
+        };
+        let point = match point.as_bytes() {
+            [x, y] => coord(*x).zip(coord(*y)).map(|(x, y)| (x, size - 1 - y)),
+            _ => None,
+        };
+        record.moves.push((color, point));
+    }
+    if record.moves.is_empty() && header.is_empty() {
+        return Err(SgfError::ParseError { message: "Not a UGF record".to_string() });
+    }
+    Ok(record)
+}
+
+/// QGS: the game record it carries, as an SGF game tree after any lines of its own, or in
+/// one of the formats `detect_kifu_format` knows.
+fn read_qgs(text: &str) -> Result<Arc<SgfTree>, SgfError> {
+    if let Some(start) = text.find("(;") {
+        return parse_sgf(text[start..].to_string());
+    }
+    match detect_kifu_format(text.to_string()) {
+        Some(format) if format != KifuFormat::Qgs => parse_kifu(text.to_string(), Some(format)),
+        _ => Err(SgfError::ParseError { message: "No game record found in the QGS file".to_string() }),
+    }
+}
+
+/// Splits "Name (3D)", "Name [3d]" or "Name 3D*" into the name and an SGF rank like "3d".
+fn split_rank(text: &str) -> (String, Option<String>) {
+    let text = text.trim();
+    if let Some(open) = text.rfind(['(', '[']).filter(|_| text.ends_with([')', ']'])) {
+        let rank = &text[open + 1..text.len() - 1];
+        if is_rank(rank) {
+            return (text[..open].trim().to_string(), Some(normalize_rank(rank)));
+        }
+    }
+    match text.rsplit_once(char::is_whitespace) {
+        Some((name, rank)) if is_rank(rank) => (name.trim().to_string(), Some(normalize_rank(rank))),
+        _ => (text.to_string(), None),
+    }
+}
+
+fn is_rank(text: &str) -> bool {
+    let text = text.trim().trim_end_matches('*').to_ascii_lowercase();
+    let digits = text.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && matches!(&text[digits.len()..], "k" | "d" | "p" | "kyu" | "dan" | "pro")
+}
+
+fn normalize_rank(rank: &str) -> String {
+    let rank = rank.trim().trim_end_matches('*').to_ascii_lowercase();
+    let digits = rank.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    match &rank[digits.len()..] {
+        "kyu" => format!("{}k", digits),
+        "dan" => format!("{}d", digits),
+        "pro" => format!("{}p", digits),
+        _ => rank.clone(),
+    }
+}
+
+/// SGF date from "2016- 7-17-...", "2002/02/23,10:47" or "20040519 [16:06]".
+fn date_from(text: &str) -> Option<String> {
+    let groups: Vec<&str> = text.split(|c: char| !c.is_ascii_digit()).filter(|g| !g.is_empty()).collect();
+    let (year, month, day) = match groups.as_slice() {
+        [compact, ..] if compact.len() == 8 => (&compact[..4], &compact[4..6], &compact[6..]),
+        [year, month, day, ..] if year.len() == 4 => (*year, *month, *day),
+        _ => return None,
+    };
+    let month: u32 = month.parse().ok().filter(|m| (1..=12).contains(m))?;
+    let day: u32 = day.parse().ok().filter(|d| (1..=31).contains(d))?;
+    Some(format!("{}-{:02}-{:02}", year, month, day))
+}
+
+/// SGF result from free text such as "White wins by resignation", "Black wins by 3.5",
+/// the Korean "흑 3.5집 승" / "백 불계승", or SGF's own "B+R" and "W+T".
+fn result_from_text(text: &str) -> String {
+    let lower = text.to_lowercase();
+    let Some(winner) = winner_of(&lower) else {
+        return String::new();
+    };
+    let score = lower
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .find_map(|n| n.parse::<f64>().ok().filter(|&s| s > 0.0));
+    let reason = lower.split_once('+').map(|(_, reason)| reason.trim());
+    let how = if lower.contains("resign") || lower.contains("불계") || reason == Some("r") {
+        "R".to_string()
+    } else if lower.contains("time") || lower.contains("시간") || reason == Some("t") {
+        "T".to_string()
+    } else if let Some(score) = score {
+        score.to_string()
+    } else {
+        String::new()
+    };
+    format!("{}+{}", winner, how)
+}
+
+/// "B" or "W" for the winner named in lower-case result text; a color that leads the
+/// text ("black wins", "b+3.5", "흑 ...") wins over one mentioned later.
+fn winner_of(lower: &str) -> Option<&'static str> {
+    let leads = |english: &str, korean: char| {
+        lower.starts_with(english)
+            || (lower.starts_with(&english[..1]) && lower[1..].starts_with('+'))
+            || lower.starts_with(korean)
+    };
+    if leads("black", '흑') || (lower.contains("black") && !lower.contains("white")) {
+        Some("B")
+    } else if leads("white", '백') || lower.contains("white") || lower.contains('백') {
+        Some("W")
+    } else if lower.contains('흑') {
+        Some("B")
+    } else {
+        None
+    }
+}
@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use crate::{Board, Point, StoneColor};
+
+/// Positions read before giving up; an unfinished reading counts as an escape.
+const MAX_NODES: u32 = 20_000;
+
+#[derive(uniffi::Record, Clone, Debug, PartialEq, Eq)]
+pub struct LadderMove {
+    pub color: StoneColor,
+    pub point: Point,
+}
+
+/// Outcome of reading out a ladder against one chain.
+#[derive(uniffi::Record, Clone, Debug)]
+pub struct LadderResult {
+    /// Whether the attacker captures the chain.
+    pub captured: bool,
+    /// Main line of the ladder, alternating colors. When the ladder works it ends with the
+    /// capturing move, or with the atari if the chain has no legal move left; otherwise it
+    /// follows the longest chase before the chain escapes.
+    pub sequence: Vec<LadderMove>,
+    /// Stones of the defender's color that the fleeing chain runs into along the sequence.
+    pub breakers: Vec<Point>,
+}
+
+#[uniffi::export]
+impl Board {
+    /// Reads a ladder against the chain at (x, y) without an engine.
+    ///
+    /// A chain in atari is read with the defender to move; a chain with two liberties is
+    /// read with the attacker to move. Other chains and empty points return `None`.
+    pub fn read_ladder(&self, x: u32, y: u32) -> Option<LadderResult> {
+        let defender = self.get_stone(x, y)?;
+        let target = (y * self.width + x) as usize;
+        let mut reader = LadderReader { target, defender, nodes: 0 };
+        let (captured, line) = match self.liberties(target).len() {
+            1 => reader.defend(self),
+            2 => reader.attack(self),
+            _ => return None,
+        };
+        let breakers = reader.breakers(self, &line);
+        Some(LadderResult {
+            captured,
+            sequence: line
+                .into_iter()
+                .map(|(color, idx)| LadderMove { color, point: self.point(idx) })
+                .collect(),
+            breakers,
+        })
+    }
+}
+
+type Line = Vec<(StoneColor, usize)>;
+
+struct LadderReader {
+    /// Any stone of the chain being chased; it stays part of the chain until it is captured.
+    target: usize,
+    defender: StoneColor,
+    nodes: u32,
+}
+
+impl LadderReader {
+    fn attacker(&self) -> StoneColor {
+        self.defender.opponent()
+    }
+
+    /// Defender in atari to move. Returns whether the chain is captured and the main line.
+    fn defend(&mut self, board: &Board) -> (bool, Line) {
+        let mut options = board.liberties(self.target);
+        for p in self.capturing_moves(board) {
+            if !options.contains(&p) {
+                options.push(p);
+            }
+        }
+
+        let mut longest: Option<Line> = None;
+        for p in options {
+            let Some(next) = play_at(board, p, self.defender) else { continue };
+            self.nodes += 1;
+            let (captured, mut line) = if next.grid[self.target].is_none() {
+                (true, vec![])
+            } else {
+                match next.liberties(self.target).as_slice() {
+                    [last] => (true, vec![(self.attacker(), *last)]),
+                    [_, _] if self.nodes < MAX_NODES => self.attack(&next),
+                    _ => (false, vec![]),
+                }
+            };
+            line.insert(0, (self.defender, p));
+            if !captured {
+                return (false, line);
+            }
+            if longest.as_ref().is_none_or(|l| line.len() > l.len()) {
+                longest = Some(line);
+            }
+        }
+        // With no move at all the line ends at the atari, so the colors keep alternating
+        (true, longest.unwrap_or_default())
+    }
+
+    /// Attacker to move against a chain with two liberties.
+    fn attack(&mut self, board: &Board) -> (bool, Line) {
+        let mut longest: Option<Line> = None;
+        for p in board.liberties(self.target) {
+            let Some(next) = play_at(board, p, self.attacker()) else { continue };
+            self.nodes += 1;
+            let (captured, mut line) = if next.liberties(self.target).len() == 1 && self.nodes < MAX_NODES {
+                self.defend(&next)
+            } else {
+                (false, vec![])
+            };
+            line.insert(0, (self.attacker(), p));
+            if captured {
+                return (true, line);
+            }
+            if longest.as_ref().is_none_or(|l| line.len() > l.len()) {
+                longest = Some(line);
+            }
+        }
+        (false, longest.unwrap_or_default())
+    }
+
+    /// Points where the defender captures an attacking chain that is itself in atari.
+    fn capturing_moves(&self, board: &Board) -> Vec<usize> {
+        let mut moves = Vec::new();
+        for s in board.chain(self.target) {
+            for n in board.adjacent(s) {
+                if board.grid[n] == Some(self.attacker()) {
+                    if let [liberty] = board.liberties(n).as_slice() {
+                        if !moves.contains(liberty) {
+                            moves.push(*liberty);
+                        }
+                    }
+                }
+            }
+        }
+        moves
+    }
+
+    /// Defender stones from the starting position that join the chain along `line`.
+    fn breakers(&self, board: &Board, line: &Line) -> Vec<Point> {
+        let original: Vec<usize> = board.chain(self.target).collect();
+        let mut breakers = Vec::new();
+        let mut current = Arc::new(board.clone());
+        for &(color, idx) in line {
+            let Some(next) = play_at(&current, idx, color) else { break };
+            current = next;
+            if color != self.defender || current.grid[self.target].is_none() {
+                continue;
+            }
+            for s in current.chain(self.target) {
+                if board.grid[s] == Some(self.defender) && !original.contains(&s) && !breakers.contains(&s) {
+                    breakers.push(s);
+                }
+            }
+        }
+        breakers.sort_unstable();
+        breakers.into_iter().map(|i| board.point(i)).collect()
+    }
+}
+
+fn play_at(board: &Board, idx: usize, color: StoneColor) -> Option<Arc<Board>> {
+    let p = board.point(idx);
+    board.try_play(p.x, p.y, color).ok().map(|result| result.board)
+}
@@ -0,0 +1,86 @@
+use crate::{repeats_position, Board, Game, Point, SgfError, StoneColor};
+
+/// Whether a move can be played, and if not, why.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum Legality {
+    Legal,
+    OutOfBounds,
+    Occupied,
+    /// The move would leave its own chain without liberties and captures nothing.
+    Suicide,
+    /// The move retakes a ko immediately.
+    Ko,
+    /// The move repeats an earlier position under the superko rule in effect.
+    /// Only reported by `Game`, which knows the history.
+    Superko,
+}
+
+impl Legality {
+    /// The error `place_stone`/`play_move` report for this reason.
+    pub(crate) fn into_error(self) -> SgfError {
+        let message = match self {
+            Legality::Legal => "Legal move",
+            Legality::OutOfBounds => "Out of bounds",
+            Legality::Occupied => "Position occupied",
+            Legality::Suicide => "Suicide move",
+            Legality::Ko => "Ko violation",
+            Legality::Superko => "Superko violation",
+        };
+        SgfError::ParseError { message: message.into() }
+    }
+}
+
+#[uniffi::export]
+impl Board {
+    /// Checks a move without playing it. Superko needs the game history; see `Game::check_move`.
+    pub fn check_move(&self, x: u32, y: u32, color: StoneColor) -> Legality {
+        match self.try_play(x, y, color) {
+            Ok(_) => Legality::Legal,
+            Err(reason) => reason,
+        }
+    }
+
+    /// Every point where `color` may play, top to bottom and left to right.
+    pub fn get_legal_moves(&self, color: StoneColor) -> Vec<Point> {
+        (0..self.grid.len())
+            .filter(|&i| self.grid[i].is_none())
+            .map(|i| self.point(i))
+            .filter(|p| self.try_play(p.x, p.y, color).is_ok())
+            .collect()
+    }
+}
+
+#[uniffi::export]
+impl Game {
+    /// Checks a move at the current node without playing it, including superko.
+    pub fn check_move(&self, x: u32, y: u32, color: StoneColor) -> Legality {
+        let mut state = self.state.lock().unwrap();
+        let board = state.current_board();
+        match board.try_play(x, y, color) {
+            Ok(result) => {
+                let history = state.path_hashes();
+                if repeats_position(state.rules.ko_rule, &history, &result.board, color) {
+                    Legality::Superko
+                } else {
+                    Legality::Legal
+                }
+            }
+            Err(reason) => reason,
+        }
+    }
+
+    /// Every point where `color` may play at the current node, including the superko check.
+    pub fn get_legal_moves(&self, color: StoneColor) -> Vec<Point> {
+        let mut state = self.state.lock().unwrap();
+        let board = state.current_board();
+        let history = state.path_hashes();
+        (0..board.grid.len())
+            .filter(|&i| board.grid[i].is_none())
+            .map(|i| board.point(i))
+            .filter(|p| match board.try_play(p.x, p.y, color) {
+                Ok(result) => !repeats_position(state.rules.ko_rule, &history, &result.board, color),
+                Err(_) => false,
+            })
+            .collect()
+    }
+}
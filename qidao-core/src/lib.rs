@@ -5,7 +5,55 @@ use std::sync::{Arc, Mutex, OnceLock};
 use thiserror::Error;
 use tokio::runtime::Runtime;
 
+pub mod annotations;
+mod chains;
+pub mod collection;
+pub mod coords;
+pub mod diagram;
+pub mod encoding;
 pub mod engine;
+pub mod estimate;
+pub mod groups;
+pub mod handicap;
+pub mod kifu;
+pub mod ladder;
+pub mod legality;
+pub mod life;
+pub mod markup;
+pub mod pdf;
+pub mod properties;
+pub mod raster;
+pub mod rules;
+pub mod scoring;
+pub mod validation;
+
+use chains::Chains;
+use coords::{check_size, is_sgf_pass, point_to_gtp, sgf_coord, sgf_to_gtp, sgf_to_point};
+
+pub use annotations::NodeAnnotation;
+pub use collection::SgfCollection;
+pub use encoding::{decode_sgf_bytes, parse_sgf_bytes, DecodedSgf};
+pub use coords::{format_move, parse_move, Move, Notation, Point, MAX_BOARD_SIZE};
+pub use diagram::{
+    default_diagram_options, print_diagram_theme, wood_diagram_theme, DiagramOptions, DiagramRegion, DiagramTheme,
+    MoveRange, StoneStyle,
+};
+pub use estimate::TerritoryEstimate;
+pub use rules::{parse_rules, rules_for_rule_set, HandicapBonus, RuleSet, Rules, ScoringType, TaxRule};
+pub use groups::Group;
+pub use handicap::fixed_handicap_points;
+pub use kifu::{detect_kifu_format, kifu_format_for_extension, parse_kifu, parse_kifu_bytes, KifuFormat};
+pub use ladder::{LadderMove, LadderResult};
+pub use legality::Legality;
+pub use life::{ChainLife, ChainStatus};
+pub use markup::{LabelStyle, Mark, NodeMarkup};
+pub use properties::{
+    raw_property, typed_property, Annotation, GameInfoField, Label, MarkupShape, Property, RootField, Segment,
+};
+pub use pdf::{default_kifu_options, KifuFigure, KifuOptions, PageSize};
+pub use raster::{default_animation_options, AnimationOptions};
+pub use scoring::{ScoreReport, ScoringSession};
+pub use validation::{validate_sgf, Diagnostic, DiagnosticKind, Repair, Severity, ValidationReport};
 
 static RUNTIME: OnceLock<Runtime> = OnceLock::new();
 
@@ -30,7 +78,10 @@ pub struct GameMetadata {
     pub event: String,
     pub game_name: String,
     pub place: String,
+    /// Board width; equal to the height on square boards.
     pub size: u32,
+    pub width: u32,
+    pub height: u32,
 }
 
 #[derive(uniffi::Record, Clone)]
@@ -54,6 +105,9 @@ pub enum SgfError {
 pub struct SgfNode {
     pub properties: Mutex<Vec<SgfProperty>>,
     pub children: Mutex<Vec<Arc<SgfNode>>>,
+    /// Properties as read from a file, each with its values as written there, so saving
+    /// writes unchanged properties back verbatim. Empty for nodes made in memory.
+    pub(crate) source: Vec<(SgfProperty, Vec<String>)>,
 }
 
 #[uniffi::export]
@@ -100,232 +154,353 @@ impl StoneColor {
     }
 }
 
-#[derive(uniffi::Object)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum SuperkoPolicy {
+    /// A move may not recreate any earlier board position.
+    Positional,
+    /// A move may not recreate an earlier position with the same player to move.
+    Situational,
+    /// Only simple ko is enforced.
+    None,
+}
+
+// --- Zobrist Hashing ---
+
+const ZOBRIST_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+fn splitmix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(ZOBRIST_SEED);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Zobrist key of a stone of `color` on grid index `idx`.
+fn zobrist_key(idx: usize, color: StoneColor) -> u64 {
+    let c = match color {
+        StoneColor::Black => 0,
+        StoneColor::White => 1,
+    };
+    splitmix64(((idx as u64) << 1) | c)
+}
+
+/// Stones captured by each side (stones of the opposite color taken off the board).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, uniffi::Record)]
+pub struct Prisoners {
+    pub black: u32,
+    pub white: u32,
+}
+
+impl Prisoners {
+    fn add(&mut self, color: StoneColor, count: u32) {
+        match color {
+            StoneColor::Black => self.black += count,
+            StoneColor::White => self.white += count,
+        }
+    }
+}
+
+/// Outcome of a successful move: the new board and the points whose stones were removed.
+#[derive(uniffi::Record)]
+pub struct MoveResult {
+    pub board: Arc<Board>,
+    pub captured: Vec<Point>,
+}
+
+#[derive(uniffi::Object, Clone)]
 pub struct Board {
-    size: u32,
+    width: u32,
+    height: u32,
     grid: Vec<Option<StoneColor>>, // Flat array for performance
+    chains: Chains, // Chain membership and liberties, updated move by move
     last_captured_pos: Option<(u32, u32)>, // Simple Ko support
+    hash: u64, // Incremental Zobrist hash of the stones on the grid
+    suicide_allowed: bool, // Multi-stone suicide (New Zealand, Tromp-Taylor)
+    prisoners: Prisoners, // Accumulated over every move that led to this board
+    last_captures: Vec<Point>, // Stones removed by the move that produced this board
 }
 
 #[uniffi::export]
 impl Board {
     #[uniffi::constructor]
-    pub fn new(size: u32) -> Arc<Self> {
-        Arc::new(Self {
-            size,
-            grid: vec![None; (size * size) as usize],
-            last_captured_pos: None,
-        })
+    pub fn new(size: u32) -> Result<Arc<Self>, SgfError> {
+        Self::with_rules(size, size, Rules::default())
     }
 
+    #[uniffi::constructor]
+    pub fn new_rectangular(width: u32, height: u32) -> Result<Arc<Self>, SgfError> {
+        Self::with_rules(width, height, Rules::default())
+    }
+
+    /// Creates an empty board whose legality checks follow `rules`. Each side must be
+    /// 1 to `MAX_BOARD_SIZE` points.
+    #[uniffi::constructor]
+    pub fn with_rules(width: u32, height: u32, rules: Rules) -> Result<Arc<Self>, SgfError> {
+        check_size(width, height)?;
+        Ok(Self::empty(width, height, rules))
+    }
+
+    /// Board width; on square boards this is simply the board size.
     pub fn get_size(&self) -> u32 {
-        self.size
+        self.width
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    /// Zobrist hash of the current stone configuration.
+    pub fn get_hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn get_prisoners(&self) -> Prisoners {
+        self.prisoners
+    }
+
+    /// Points whose stones were removed by the move that produced this board.
+    pub fn get_last_captures(&self) -> Vec<Point> {
+        self.last_captures.clone()
     }
 
     pub fn get_stone(&self, x: u32, y: u32) -> Option<StoneColor> {
-        if x >= self.size || y >= self.size {
+        if x >= self.width || y >= self.height {
             return None;
         }
-        self.grid[(y * self.size + x) as usize]
+        self.grid[(y * self.width + x) as usize]
     }
 
     /// Forcefully places a stone without checking rules (for setup stones).
     pub fn with_stone(&self, x: u32, y: u32, color: Option<StoneColor>) -> Arc<Board> {
-        if x >= self.size || y >= self.size {
-            return Arc::new(Board {
-                size: self.size,
-                grid: self.grid.clone(),
-                last_captured_pos: self.last_captured_pos,
-            });
+        let mut board = self.clone();
+        board.last_captures.clear();
+        if x >= self.width || y >= self.height {
+            return Arc::new(board);
         }
-        let mut new_grid = self.grid.clone();
-        new_grid[(y * self.size + x) as usize] = color;
-        Arc::new(Board {
-            size: self.size,
-            grid: new_grid,
-            last_captured_pos: self.last_captured_pos,
-        })
+        let idx = (y * self.width + x) as usize;
+        if let Some(old) = board.grid[idx] {
+            board.hash ^= zobrist_key(idx, old);
+        }
+        if let Some(new) = color {
+            board.hash ^= zobrist_key(idx, new);
+        }
+        board.grid[idx] = color;
+        // Removing a stone can split a chain, so setup edits rebuild the chains from scratch
+        board.rebuild_chains();
+        Arc::new(board)
     }
 
-    /// Attempts to place a stone. Returns true if successful.
+    /// Returns the board after a pass: stones are unchanged and a pending ko is lifted.
+    pub fn pass(&self) -> Arc<Board> {
+        let mut board = self.clone();
+        board.last_captured_pos = None;
+        board.last_captures.clear();
+        Arc::new(board)
+    }
+
+    /// Attempts to place a stone, returning the resulting board.
     pub fn place_stone(&self, x: u32, y: u32, color: StoneColor) -> Result<Arc<Board>, SgfError> {
-        if x >= self.size || y >= self.size {
-            return Err(SgfError::ParseError { message: "Out of bounds".into() });
+        self.play(x, y, color).map(|result| result.board)
+    }
+
+    /// Attempts to place a stone, reporting which stones were captured.
+    pub fn play(&self, x: u32, y: u32, color: StoneColor) -> Result<MoveResult, SgfError> {
+        self.try_play(x, y, color).map_err(Legality::into_error)
+    }
+}
+
+impl Board {
+    /// An empty board of a size that is already known to be valid.
+    pub(crate) fn empty(width: u32, height: u32, rules: Rules) -> Arc<Self> {
+        let points = (width * height) as usize;
+        Arc::new(Self {
+            width,
+            height,
+            grid: vec![None; points],
+            chains: Chains::new(points),
+            last_captured_pos: None,
+            hash: 0,
+            suicide_allowed: rules.suicide_allowed,
+            prisoners: Prisoners::default(),
+            last_captures: vec![],
+        })
+    }
+
+    /// `play` with the reason for an illegal move kept typed.
+    fn try_play(&self, x: u32, y: u32, color: StoneColor) -> Result<MoveResult, Legality> {
+        if x >= self.width || y >= self.height {
+            return Err(Legality::OutOfBounds);
         }
         if self.get_stone(x, y).is_some() {
-            return Err(SgfError::ParseError { message: "Position occupied".into() });
+            return Err(Legality::Occupied);
         }
 
-        let mut new_grid = self.grid.clone();
-        let idx = (y * self.size + x) as usize;
-        new_grid[idx] = Some(color);
+        let mut board = self.clone();
+        let idx = (y * self.width + x) as usize;
+        board.add_stone(idx, color);
+        let mut removed = Vec::new();
 
         // 1. Check for captures of opponent
         let opponent = color.opponent();
-        let mut captured_any = false;
-        let mut last_cap = None;
-        let mut capture_count = 0;
-
-        for (nx, ny) in self.neighbors(x, y) {
-            if new_grid[(ny * self.size + nx) as usize] == Some(opponent) {
-                if self.count_liberties(&new_grid, nx, ny) == 0 {
-                    let captured = self.get_group(&new_grid, nx, ny);
-                    capture_count += captured.len();
-                    for (cx, cy) in &captured {
-                        new_grid[(cy * self.size + cx) as usize] = None;
-                        last_cap = Some((*cx, *cy));
-                    }
-                    captured_any = true;
-                }
+        for n in self.adjacent(idx) {
+            if board.grid[n] == Some(opponent) && !board.chains.has_liberties(n) {
+                removed.extend(board.remove_chain(n));
             }
         }
-
-        // 2. Check for suicide (unless it captures)
-        if !captured_any && self.count_liberties(&new_grid, x, y) == 0 {
-            return Err(SgfError::ParseError { message: "Suicide move".into() });
+        let capture_count = removed.len();
+        board.prisoners.add(color, capture_count as u32);
+
+        // 2. Check for suicide (unless it captures).
+        // Single-stone suicide never changes the position, so it is always rejected.
+        if capture_count == 0 && !board.chains.has_liberties(idx) {
+            if !self.suicide_allowed || board.chains.size(idx) == 1 {
+                return Err(Legality::Suicide);
+            }
+            let own_group = board.remove_chain(idx);
+            board.prisoners.add(opponent, own_group.len() as u32);
+            removed.extend(own_group);
         }
 
         // 3. Simple Ko check (if exactly one stone was captured)
+        board.last_captured_pos = None;
         if capture_count == 1 {
-            if let Some(pos) = self.last_captured_pos {
-                if pos == (x, y) {
-                    return Err(SgfError::ParseError { message: "Ko violation".into() });
-                }
+            if self.last_captured_pos == Some((x, y)) {
+                return Err(Legality::Ko);
             }
-        } else {
-            last_cap = None;
+            let cap = removed[0];
+            board.last_captured_pos = Some((cap as u32 % self.width, cap as u32 / self.width));
         }
 
-        Ok(Arc::new(Board {
-            size: self.size,
-            grid: new_grid,
-            last_captured_pos: last_cap,
-        }))
+        let mut captured: Vec<Point> = removed.into_iter().map(|i| self.point(i)).collect();
+        captured.sort_by_key(|p| (p.y, p.x));
+        board.last_captures = captured.clone();
+        Ok(MoveResult { board: Arc::new(board), captured })
     }
-}
 
-impl Board {
-    fn neighbors(&self, x: u32, y: u32) -> Vec<(u32, u32)> {
-        let mut n = Vec::new();
-        if x > 0 { n.push((x - 1, y)); }
-        if x < self.size - 1 { n.push((x + 1, y)); }
-        if y > 0 { n.push((x, y - 1)); }
-        if y < self.size - 1 { n.push((x, y + 1)); }
-        n
-    }
-
-    fn get_group(&self, grid: &[Option<StoneColor>], x: u32, y: u32) -> Vec<(u32, u32)> {
-        let color = grid[(y * self.size + x) as usize];
-        let mut group = Vec::new();
-        let mut stack = vec![(x, y)];
-        let mut visited = std::collections::HashSet::new();
-
-        while let Some((cx, cy)) = stack.pop() {
-            if !visited.insert((cx, cy)) { continue; }
-            if grid[(cy * self.size + cx) as usize] == color {
-                group.push((cx, cy));
-                for (nx, ny) in self.neighbors(cx, cy) {
-                    stack.push((nx, ny));
-                }
-            }
+    fn point(&self, idx: usize) -> Point {
+        Point {
+            x: idx as u32 % self.width,
+            y: idx as u32 / self.width,
         }
-        group
     }
 
-    fn count_liberties(&self, grid: &[Option<StoneColor>], x: u32, y: u32) -> u32 {
-        let color = grid[(y * self.size + x) as usize];
-        let mut liberties = std::collections::HashSet::new();
-        let mut stack = vec![(x, y)];
-        let mut visited = std::collections::HashSet::new();
-
-        while let Some((cx, cy)) = stack.pop() {
-            if !visited.insert((cx, cy)) { continue; }
-            if grid[(cy * self.size + cx) as usize] == color {
-                for (nx, ny) in self.neighbors(cx, cy) {
-                    stack.push((nx, ny));
+    /// Grid indices of the points orthogonally adjacent to `idx`.
+    fn adjacent(&self, idx: usize) -> impl Iterator<Item = usize> {
+        let (w, h) = (self.width as usize, self.height as usize);
+        let (x, y) = (idx % w, idx / w);
+        [
+            (x > 0).then(|| idx - 1),
+            (x + 1 < w).then(|| idx + 1),
+            (y > 0).then(|| idx - w),
+            (y + 1 < h).then(|| idx + w),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Grid indices of the chain containing the stone at `idx`.
+    fn chain(&self, idx: usize) -> impl Iterator<Item = usize> + '_ {
+        self.chains.stones(idx)
+    }
+
+    /// Puts a stone on an empty point and joins it to its neighbors, without capturing.
+    fn add_stone(&mut self, idx: usize, color: StoneColor) {
+        self.grid[idx] = Some(color);
+        self.hash ^= zobrist_key(idx, color);
+        let liberties = self.adjacent(idx).filter(|&n| self.grid[n].is_none()).count();
+        self.chains.add_stone(idx, liberties as u32);
+        for n in self.adjacent(idx) {
+            if let Some(c) = self.grid[n] {
+                self.chains.remove_liberty(n);
+                if c == color {
+                    self.chains.merge(idx, n);
                 }
-            } else if grid[(cy * self.size + cx) as usize].is_none() {
-                liberties.insert((cx, cy));
             }
         }
-        liberties.len() as u32
     }
-}
 
-fn sgf_to_gtp(sgf_coord: &str, size: u32) -> String {
-    if sgf_coord.is_empty() {
-        return "pass".to_string();
-    }
-    let chars: Vec<char> = sgf_coord.chars().collect();
-    if chars.len() < 2 {
-        return "pass".to_string();
+    /// Takes the chain containing `idx` off the board, returning its points.
+    fn remove_chain(&mut self, idx: usize) -> Vec<usize> {
+        let stones: Vec<usize> = self.chain(idx).collect();
+        for &s in &stones {
+            if let Some(color) = self.grid[s].take() {
+                self.hash ^= zobrist_key(s, color);
+            }
+        }
+        for &s in &stones {
+            for n in self.adjacent(s) {
+                if self.grid[n].is_some() {
+                    self.chains.add_liberty(n);
+                }
+            }
+        }
+        stones
     }
 
-    let x = (chars[0] as u32).saturating_sub('a' as u32);
-    let y = (chars[1] as u32).saturating_sub('a' as u32);
-
-    if x >= size || y >= size {
-        return "pass".to_string();
+    fn rebuild_chains(&mut self) {
+        self.chains = Chains::new(self.grid.len());
+        for idx in 0..self.grid.len() {
+            let Some(color) = self.grid[idx] else { continue };
+            let liberties = self.adjacent(idx).filter(|&n| self.grid[n].is_none()).count();
+            self.chains.add_stone(idx, liberties as u32);
+            // Only neighbors already visited (left and above) have chains to join
+            for n in self.adjacent(idx).filter(|&n| n < idx) {
+                if self.grid[n] == Some(color) {
+                    self.chains.merge(idx, n);
+                }
+            }
+        }
     }
+}
 
-    let col_char = if x < 8 {
-        (b'A' + x as u8) as char
-    } else {
-        (b'A' + x as u8 + 1) as char // Skip 'I'
+/// Parses an SGF `SZ` value: "19" for square boards or "19:13" for width:height.
+fn parse_board_size(value: &str) -> Option<(u32, u32)> {
+    let (width, height) = match value.split_once(':') {
+        Some((w, h)) => (w.trim().parse().ok()?, h.trim().parse().ok()?),
+        None => {
+            let size = value.trim().parse().ok()?;
+            (size, size)
+        }
     };
-
-    let row = size - y;
-    format!("{}{}", col_char, row)
+    let valid = 1..=MAX_BOARD_SIZE;
+    (valid.contains(&width) && valid.contains(&height)).then_some((width, height))
 }
 
-fn convert_node(node: &ParserNode<Prop>) -> Arc<SgfNode> {
-    let properties = node.properties().map(|prop: &Prop| {
-        let s = prop.to_string();
-        let id = prop.identifier();
-
-        let mut values = Vec::new();
-        let mut current_value = String::new();
-        let mut in_brackets = false;
-        let mut escaped = false;
-
-        // s is "ID[val1][val2]"
-        for c in s.chars().skip(id.len()) {
-            if escaped {
-                current_value.push(c);
-                escaped = false;
-            } else if c == '\\' {
-                escaped = true;
-            } else if c == '[' {
-                if in_brackets {
-                    current_value.push(c);
-                } else {
-                    in_brackets = true;
-                }
-            } else if c == ']' {
-                if escaped {
-                    current_value.push(c);
-                    escaped = false;
-                } else {
-                    values.push(current_value.clone());
-                    current_value.clear();
-                    in_brackets = false;
-                }
-            } else {
-                current_value.push(c);
-            }
-        }
+fn board_size_value(width: u32, height: u32) -> String {
+    if width == height {
+        width.to_string()
+    } else {
+        format!("{}:{}", width, height)
+    }
+}
 
-        SgfProperty {
-            identifier: id,
-            values,
-        }
-    }).collect();
+/// Converts `node` and its subtree; `source` yields the written form of each node in file
+/// order, as `properties::source_nodes` reads it.
+fn convert_node(node: &ParserNode<Prop>, source: &mut impl Iterator<Item = properties::SourceNode>) -> Arc<SgfNode> {
+    let properties: Vec<SgfProperty> = node
+        .properties()
+        .map(|prop| SgfProperty { identifier: prop.identifier(), values: properties::raw_values(prop) })
+        .collect();
+    let written = source.next().unwrap_or_default();
+    // Identifiers the parser kept once; anything ambiguous is saved in canonical form
+    let kept = properties
+        .iter()
+        .filter_map(|prop| match written.iter().filter(|(id, _)| *id == prop.identifier).collect::<Vec<_>>()[..] {
+            [(_, values)] => Some((prop.clone(), values.clone())),
+            _ => None,
+        })
+        .collect();
 
-    let children = node.children().map(|c| convert_node(c)).collect();
+    let children = node.children().map(|child| convert_node(child, source)).collect();
 
     Arc::new(SgfNode {
         properties: Mutex::new(properties),
-        children: Mutex::new(children)
+        children: Mutex::new(children),
+        source: kept,
     })
 }
 
@@ -334,12 +509,21 @@ fn serialize_node(node: &Arc<SgfNode>, out: &mut String) {
     let props = node.properties.lock().unwrap();
     for prop in props.iter() {
         out.push_str(&prop.identifier);
-        for val in &prop.values {
-            out.push('[');
-            // Basic escaping
-            let escaped = val.replace('\\', "\\\\").replace(']', "\\]");
-            out.push_str(&escaped);
-            out.push(']');
+        match node.source.iter().find(|(read, _)| read.identifier == prop.identifier && read.values == prop.values) {
+            Some((_, written)) => {
+                for val in written {
+                    out.push('[');
+                    out.push_str(val);
+                    out.push(']');
+                }
+            }
+            None => {
+                for val in &prop.values {
+                    out.push('[');
+                    out.push_str(&properties::escape_value(&prop.identifier, val));
+                    out.push(']');
+                }
+            }
         }
     }
 
@@ -357,60 +541,226 @@ fn serialize_node(node: &Arc<SgfNode>, out: &mut String) {
 
 #[uniffi::export]
 pub fn parse_sgf(sgf_content: String) -> Result<Arc<SgfTree>, SgfError> {
-    let trimmed = sgf_content.trim().trim_matches('\0').trim().to_string();
+    let root = parse_game_trees(sgf_content, false)?.swap_remove(0);
+    Ok(Arc::new(SgfTree { root }))
+}
+
+/// Parses every game tree in `sgf_content`, in file order. Never returns an empty list.
+/// `transcoded` says the text was decoded from another encoding than UTF-8.
+fn parse_game_trees(sgf_content: String, transcoded: bool) -> Result<Vec<Arc<SgfNode>>, SgfError> {
+    let trimmed = properties::remove_soft_line_breaks(sgf_content.trim().trim_matches('\0').trim());
     if trimmed.is_empty() {
         return Err(SgfError::ParseError { message: "Empty SGF content".to_string() });
     }
+    let convert = |trees: Vec<ParserNode<Prop>>, text: &str| -> Result<Vec<Arc<SgfNode>>, SgfError> {
+        if trees.is_empty() {
+            return Err(SgfError::ParseError { message: "No tree found in SGF".to_string() });
+        }
+        fn count(node: &ParserNode<Prop>) -> usize {
+            1 + node.children().map(count).sum::<usize>()
+        }
+        // Nodes pair up in file order; if the counts disagree, nothing is kept verbatim
+        let mut written = properties::source_nodes(text);
+        if written.len() != trees.iter().map(count).sum::<usize>() {
+            written.clear();
+        }
+        let mut written = written.into_iter();
+        let roots: Vec<Arc<SgfNode>> = trees.iter().map(|tree| convert_node(tree, &mut written)).collect();
+        // Values are UTF-8 once they are in memory, whatever the file was written in. Files
+        // without CA read as UTF-8 stay that way; others get one so other readers agree.
+        for root in &roots {
+            let mut props = root.properties.lock().unwrap();
+            if transcoded || props.iter().any(|p| p.identifier == "CA") {
+                set_property(&mut props, "CA", vec!["UTF-8".to_string()]);
+            }
+        }
+        Ok(roots)
+    };
 
-    // Try parsing normally first
     match parse(&trimmed) {
-        Ok(trees) => {
-            if let Some(first_tree) = trees.iter().next() {
-                Ok(Arc::new(SgfTree {
-                    root: convert_node(first_tree),
-                }))
-            } else {
-                Err(SgfError::ParseError { message: "No tree found in SGF".to_string() })
+        Ok(trees) => convert(trees, &trimmed),
+        Err(e) => {
+            // Truncated downloads are common; close what is still open, as `validate_sgf` reports
+            let repaired = validation::truncation_repair(&trimmed).map(|closing| trimmed.clone() + &closing);
+            match repaired.and_then(|text| Some((parse(&text).ok()?, text))) {
+                Some((trees, text)) => convert(trees, &text),
+                None => Err(SgfError::ParseError { message: e.to_string() }),
             }
         }
-        Err(e) => {
-            // If it fails, try to "fix" it if it looks truncated
-            // This is a common issue with some SGF sources
-
-            // Try different combinations of closing brackets and parentheses
-            for brackets in 0..3 {
-                let mut base = trimmed.clone();
-                for _ in 0..brackets {
-                    base.push(']');
-                }
+    }
+}
 
-                for parens in 1..10 {
-                    let mut attempt = base.clone();
-                    for _ in 0..parens {
-                        attempt.push(')');
-                    }
+struct GameState {
+    root: Arc<SgfNode>,
+    current_node: Arc<SgfNode>,
+    history: Vec<Arc<SgfNode>>,
+    board_cache: std::collections::HashMap<usize, Arc<Board>>,
+    width: u32,
+    height: u32,
+    rules: Rules,
+}
+
+impl GameState {
+    /// Nodes from the root down to and including the current node.
+    fn path(&self) -> Vec<Arc<SgfNode>> {
+        let mut path = self.history.clone();
+        path.push(self.current_node.clone());
+        path
+    }
+
+    /// Returns the board at the current node, replaying and caching every node on the path.
+    fn current_board(&mut self) -> Arc<Board> {
+        let current_ptr = Arc::as_ptr(&self.current_node) as usize;
 
-                    if let Ok(trees) = parse(&attempt) {
-                        if let Some(first_tree) = trees.iter().next() {
-                            return Ok(Arc::new(SgfTree {
-                                root: convert_node(first_tree),
-                            }));
+        if let Some(board) = self.board_cache.get(&current_ptr) {
+            return board.clone();
+        }
+        let path = self.path();
+        self.board_at(&path)
+    }
+
+    /// Returns the board after the last node of `path`, a line of nodes from the root,
+    /// replaying and caching every node on it.
+    pub(crate) fn board_at(&mut self, path: &[Arc<SgfNode>]) -> Arc<Board> {
+        // If not in cache, we must compute it from the path.
+        // This can happen after loading an SGF or jumping to a node.
+        // Replay starts from the deepest ancestor whose board is already cached.
+        let mut current_board = Board::empty(self.width, self.height, self.rules);
+        let mut start = 0;
+        for i in (0..path.len()).rev() {
+            if let Some(cached) = self.board_cache.get(&(Arc::as_ptr(&path[i]) as usize)) {
+                current_board = cached.clone();
+                start = i + 1;
+                break;
+            }
+        }
+        for node in &path[start..] {
+            let node_ptr = Arc::as_ptr(node) as usize;
+
+            // Apply moves and setup stones in this node
+            let props = node.properties.lock().unwrap();
+            for prop in props.iter() {
+                match prop.identifier.as_str() {
+                    "B" | "W" => {
+                        let color = if prop.identifier == "B" { StoneColor::Black } else { StoneColor::White };
+                        if let Some(coords) = prop.values.first() {
+                            if is_sgf_pass(coords, self.width, self.height) {
+                                current_board = current_board.pass();
+                            } else if let Some((x, y)) = sgf_to_point(coords) {
+                                if let Ok(next_board) = current_board.place_stone(x, y, color) {
+                                    current_board = next_board;
+                                }
+                            }
+                        }
+                    }
+                    "AB" | "AW" | "AE" => {
+                        let color = if prop.identifier == "AB" { Some(StoneColor::Black) }
+                                   else if prop.identifier == "AW" { Some(StoneColor::White) }
+                                   else { None };
+                        for coords in &prop.values {
+                            if let Some((x, y)) = sgf_to_point(coords) {
+                                current_board = current_board.with_stone(x, y, color);
+                            }
                         }
                     }
+                    _ => {}
                 }
             }
+            self.board_cache.insert(node_ptr, current_board.clone());
+        }
+
+        current_board
+    }
 
-            Err(SgfError::ParseError { message: e.to_string() })
+    /// Zobrist hashes of every position along the current path, paired with
+    /// the side to move in it, as `side_to_move` counts it.
+    fn path_hashes(&mut self) -> Vec<(u64, StoneColor)> {
+        self.current_board();
+        let mut to_move = StoneColor::Black;
+        self.path()
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                let hash = self.board_cache
+                    .get(&(Arc::as_ptr(node) as usize))
+                    .map(|b| b.hash)
+                    .unwrap_or(0);
+                to_move = turn_after(&node.properties.lock().unwrap(), i == 0, to_move);
+                (hash, to_move)
+            })
+            .collect()
+    }
+
+    /// Checks whether `board`, reached by a move of `color`, repeats an earlier position.
+    fn violates_superko(&mut self, board: &Board, color: StoneColor) -> bool {
+        if self.rules.ko_rule == SuperkoPolicy::None {
+            return false;
         }
+        let history = self.path_hashes();
+        repeats_position(self.rules.ko_rule, &history, board, color)
     }
 }
 
-struct GameState {
-    root: Arc<SgfNode>,
-    current_node: Arc<SgfNode>,
-    history: Vec<Arc<SgfNode>>,
-    board_cache: std::collections::HashMap<usize, Arc<Board>>,
-    size: u32,
+/// Whether `board`, reached by a move of `color`, repeats a position of `history`
+/// (as returned by `GameState::path_hashes`) under the given superko policy.
+fn repeats_position(policy: SuperkoPolicy, history: &[(u64, StoneColor)], board: &Board, color: StoneColor) -> bool {
+    match policy {
+        SuperkoPolicy::None => false,
+        SuperkoPolicy::Positional => history
+            .iter()
+            .any(|(hash, _)| *hash == board.hash),
+        SuperkoPolicy::Situational => history
+            .iter()
+            .any(|(hash, to_move)| *hash == board.hash && *to_move == color.opponent()),
+    }
+}
+
+/// Walks `path` from the root and returns who plays next.
+///
+/// White starts in handicap games (`HA` of 2 or more), Black otherwise. A move hands
+/// the turn to the opponent, `PL` overrides the turn explicitly, and setup-only nodes
+/// leave it unchanged.
+fn side_to_move(path: &[Arc<SgfNode>]) -> StoneColor {
+    path.iter().enumerate().fold(StoneColor::Black, |next, (i, node)| {
+        turn_after(&node.properties.lock().unwrap(), i == 0, next)
+    })
+}
+
+/// Who plays after a node with `props`, `next` being who was to play before it;
+/// one step of `side_to_move`.
+pub(crate) fn turn_after(props: &[SgfProperty], root: bool, mut next: StoneColor) -> StoneColor {
+    if root && handicap_of(props) >= 2 {
+        next = StoneColor::White;
+    }
+    for prop in props.iter() {
+        match prop.identifier.as_str() {
+            "B" => next = StoneColor::White,
+            "W" => next = StoneColor::Black,
+            _ => {}
+        }
+    }
+    let player = props.iter()
+        .find(|p| p.identifier == "PL")
+        .and_then(|p| p.values.first())
+        .and_then(|v| parse_color(v));
+    player.unwrap_or(next)
+}
+
+/// Value of the `HA` property, or 0 when absent.
+fn handicap_of(props: &[SgfProperty]) -> u32 {
+    props.iter()
+        .find(|p| p.identifier == "HA")
+        .and_then(|p| p.values.first())
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .unwrap_or(0)
+}
+
+fn parse_color(value: &str) -> Option<StoneColor> {
+    match value.trim().chars().next() {
+        Some('B') | Some('b') => Some(StoneColor::Black),
+        Some('W') | Some('w') => Some(StoneColor::White),
+        _ => None,
+    }
 }
 
 #[derive(uniffi::Object)]
@@ -421,81 +771,57 @@ pub struct Game {
 #[uniffi::export]
 impl Game {
     #[uniffi::constructor]
-    pub fn new(size: u32) -> Arc<Self> {
+    pub fn new(size: u32) -> Result<Arc<Self>, SgfError> {
+        Self::new_rectangular(size, size)
+    }
+
+    /// Creates an empty game; each side must be 1 to `MAX_BOARD_SIZE` points.
+    #[uniffi::constructor]
+    pub fn new_rectangular(width: u32, height: u32) -> Result<Arc<Self>, SgfError> {
+        check_size(width, height)?;
         let root = Arc::new(SgfNode {
             properties: Mutex::new(vec![SgfProperty {
                 identifier: "SZ".to_string(),
-                values: vec![size.to_string()],
+                values: vec![board_size_value(width, height)],
             }]),
             children: Mutex::new(vec![]),
+            source: vec![],
         });
 
+        let rules = Rules::default();
         let mut board_cache = std::collections::HashMap::new();
-        board_cache.insert(Arc::as_ptr(&root) as usize, Board::new(size));
+        board_cache.insert(Arc::as_ptr(&root) as usize, Board::empty(width, height, rules));
 
-        Arc::new(Self {
+        Ok(Arc::new(Self {
             state: Mutex::new(GameState {
                 root: root.clone(),
                 current_node: root,
                 history: vec![],
                 board_cache,
-                size,
+                width,
+                height,
+                rules,
             }),
-        })
+        }))
+    }
+
+    /// Creates a new game with `handicap` stones on the standard star points.
+    #[uniffi::constructor]
+    pub fn with_handicap(size: u32, handicap: u32) -> Result<Arc<Self>, SgfError> {
+        let game = Self::new(size)?;
+        game.set_fixed_handicap(handicap)?;
+        Ok(game)
     }
 
     #[uniffi::constructor]
     pub fn from_sgf(sgf_content: String) -> Result<Arc<Self>, SgfError> {
         let tree = parse_sgf(sgf_content)?;
-        let root = tree.root();
-
-        // Try to find size
-        let size = {
-            let props = root.properties.lock().unwrap();
-            props.iter()
-                .find(|p| p.identifier == "SZ")
-                .and_then(|p| p.values.first())
-                .and_then(|v| v.parse::<u32>().ok())
-                .unwrap_or(19)
-        };
-
-        let mut board_cache = std::collections::HashMap::new();
-        board_cache.insert(Arc::as_ptr(&root) as usize, Board::new(size));
-
-        Ok(Arc::new(Self {
-            state: Mutex::new(GameState {
-                root: root.clone(),
-                current_node: root,
-                history: vec![],
-                board_cache,
-                size,
-            }),
-        }))
+        Ok(Self::from_root(tree.root()))
     }
 
     pub fn get_metadata(&self) -> GameMetadata {
         let state = self.state.lock().unwrap();
-        let props = state.root.properties.lock().unwrap();
-
-        let mut meta = GameMetadata::default();
-        meta.size = state.size;
-
-        for p in props.iter() {
-            match p.identifier.as_str() {
-                "PB" => meta.black_name = p.values.first().cloned().unwrap_or_default(),
-                "BR" => meta.black_rank = p.values.first().cloned().unwrap_or_default(),
-                "PW" => meta.white_name = p.values.first().cloned().unwrap_or_default(),
-                "WR" => meta.white_rank = p.values.first().cloned().unwrap_or_default(),
-                "KM" => meta.komi = p.values.first().and_then(|v| v.parse().ok()).unwrap_or(0.0),
-                "RE" => meta.result = p.values.first().cloned().unwrap_or_default(),
-                "DT" => meta.date = p.values.first().cloned().unwrap_or_default(),
-                "EV" => meta.event = p.values.first().cloned().unwrap_or_default(),
-                "GN" => meta.game_name = p.values.first().cloned().unwrap_or_default(),
-                "PC" => meta.place = p.values.first().cloned().unwrap_or_default(),
-                _ => {}
-            }
-        }
-        meta
+        metadata_of(&state.root, state.width, state.height)
     }
 
     pub fn get_current_node(&self) -> Arc<SgfNode> {
@@ -531,6 +857,13 @@ impl Game {
         let state = self.state.lock().unwrap();
         let mut props = state.root.properties.lock().unwrap();
 
+        // Rectangular boards are described by width/height; square ones by size alone
+        let board_size = if metadata.width > 0 && metadata.height > 0 && metadata.width != metadata.height {
+            board_size_value(metadata.width, metadata.height)
+        } else {
+            metadata.size.to_string()
+        };
+
         let updates = [
             ("PB", metadata.black_name),
             ("BR", metadata.black_rank),
@@ -542,18 +875,11 @@ impl Game {
             ("EV", metadata.event),
             ("GN", metadata.game_name),
             ("PC", metadata.place),
-            ("SZ", metadata.size.to_string()),
+            ("SZ", board_size),
         ];
 
         for (id, val) in updates {
-            if let Some(p) = props.iter_mut().find(|p| p.identifier == id) {
-                p.values = vec![val];
-            } else {
-                props.push(SgfProperty {
-                    identifier: id.to_string(),
-                    values: vec![val],
-                });
-            }
+            set_property(&mut props, id, vec![val]);
         }
     }
 
@@ -582,61 +908,97 @@ impl Game {
     }
 
     pub fn get_board(&self) -> Arc<Board> {
-        let mut state = self.state.lock().unwrap();
-        let current_ptr = Arc::as_ptr(&state.current_node) as usize;
+        self.state.lock().unwrap().current_board()
+    }
 
-        if let Some(board) = state.board_cache.get(&current_ptr) {
-            return board.clone();
-        }
+    /// Prisoners held by each side at the current node, counted along the current path.
+    pub fn get_prisoners(&self) -> Prisoners {
+        self.get_board().get_prisoners()
+    }
 
-        // If not in cache, we must compute it from the path.
-        // This can happen after loading an SGF or jumping to a node.
-        let mut path = state.history.clone();
-        path.push(state.current_node.clone());
+    /// Starts counting the current position with the game's rules, komi and handicap.
+    pub fn start_scoring(&self) -> Arc<ScoringSession> {
+        let board = self.get_board();
+        let komi = self.get_metadata().komi;
+        let state = self.state.lock().unwrap();
+        let handicap = handicap_of(&state.root.properties.lock().unwrap());
+        ScoringSession::new(board, state.rules, komi, handicap)
+    }
 
-        let mut current_board = Board::new(state.size);
-        for node in path {
-            let node_ptr = Arc::as_ptr(&node) as usize;
-            if let Some(cached) = state.board_cache.get(&node_ptr) {
-                current_board = cached.clone();
-                continue;
-            }
+    pub fn get_rules(&self) -> Rules {
+        self.state.lock().unwrap().rules
+    }
 
-            // Apply moves and setup stones in this node
-            let props = node.properties.lock().unwrap();
-            for prop in props.iter() {
-                match prop.identifier.as_str() {
-                    "B" | "W" => {
-                        let color = if prop.identifier == "B" { StoneColor::Black } else { StoneColor::White };
-                        if let Some(coords) = prop.values.first() {
-                            if coords.len() == 2 {
-                                let x = coords.as_bytes()[0] as i32 - 'a' as i32;
-                                let y = coords.as_bytes()[1] as i32 - 'a' as i32;
-                                if let Ok(next_board) = current_board.place_stone(x as u32, y as u32, color) {
-                                    current_board = next_board;
-                                }
-                            }
-                        }
-                    }
-                    "AB" | "AW" | "AE" => {
-                        let color = if prop.identifier == "AB" { Some(StoneColor::Black) }
-                                   else if prop.identifier == "AW" { Some(StoneColor::White) }
-                                   else { None };
-                        for coords in &prop.values {
-                            if coords.len() == 2 {
-                                let x = coords.as_bytes()[0] as i32 - 'a' as i32;
-                                let y = coords.as_bytes()[1] as i32 - 'a' as i32;
-                                current_board = current_board.with_stone(x as u32, y as u32, color);
-                            }
-                        }
-                    }
-                    _ => {}
-                }
+    /// Replaces the rule set, records it in the root `RU` property and
+    /// drops cached boards since legality may have changed. An `RU` that already
+    /// names these rules is kept as written, so "kr" stays "kr".
+    pub fn set_rules(&self, rules: Rules) {
+        let mut state = self.state.lock().unwrap();
+        state.rules = rules;
+        {
+            let mut props = state.root.properties.lock().unwrap();
+            let recorded = props
+                .iter()
+                .find(|p| p.identifier == "RU")
+                .and_then(|p| p.values.first())
+                .and_then(|v| Rules::from_sgf(v));
+            if recorded != Some(rules) {
+                set_property(&mut props, "RU", vec![rules.to_sgf()]);
             }
-            state.board_cache.insert(node_ptr, current_board.clone());
         }
+        state.board_cache.clear();
+    }
 
-        current_board
+    pub fn get_superko_policy(&self) -> SuperkoPolicy {
+        self.state.lock().unwrap().rules.ko_rule
+    }
+
+    pub fn set_superko_policy(&self, policy: SuperkoPolicy) {
+        let rules = Rules { ko_rule: policy, ..self.get_rules() };
+        self.set_rules(rules);
+    }
+
+    /// Builds a KataGo analysis query for the current position, with the
+    /// game's rules, komi and board size filled in. Fails if a move or setup
+    /// stone on the way is not a point on the board.
+    pub fn get_analysis_query(&self, id: String) -> Result<String, SgfError> {
+        let moves = self.get_analysis_moves()?;
+        let initial_stones = self.get_initial_stones()?;
+        let metadata = self.get_metadata();
+        let rules = self.get_rules();
+        let initial_player = match self.get_initial_player() {
+            StoneColor::Black => "B",
+            StoneColor::White => "W",
+        };
+
+        let to_pairs = |list: Vec<Vec<String>>| -> Vec<(String, String)> {
+            list.into_iter()
+                .filter_map(|m| match m.as_slice() {
+                    [color, vertex] => Some((color.clone(), vertex.clone())),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let turn = moves.len() as u32;
+        let query = engine::AnalysisQuery {
+            id,
+            moves: to_pairs(moves),
+            initial_stones: to_pairs(initial_stones),
+            initial_player: Some(initial_player.to_string()),
+            rules: rules.to_katago(),
+            komi: metadata.komi,
+            board_x_size: metadata.width,
+            board_y_size: metadata.height,
+            analyze_turns: vec![turn],
+            max_visits: None,
+            max_time: None,
+            report_during_search_every: None,
+            include_ownership: None,
+            include_policy: None,
+            priority: None,
+        };
+        Ok(serde_json::to_string(&query).unwrap_or_default())
     }
 
     pub fn get_move_count(&self) -> u32 {
@@ -648,17 +1010,140 @@ impl Game {
         get_max_depth(&state.root)
     }
 
+    /// Side to move at the current node, following moves, `PL` and handicap along the path.
     pub fn get_next_color(&self) -> StoneColor {
         let state = self.state.lock().unwrap();
-        // Simple heuristic: if last move was Black, next is White.
-        // In a real SGF, we'd check the properties of the current node.
-        let props = state.current_node.properties.lock().unwrap();
-        for prop in props.iter() {
-            if prop.identifier == "B" { return StoneColor::White; }
-            if prop.identifier == "W" { return StoneColor::Black; }
+        side_to_move(&state.path())
+    }
+
+    /// Side to move before the first move of the game (KataGo's `initialPlayer`): `PL` at
+    /// the root, else White in handicap games. A move in the root node does not count.
+    pub fn get_initial_player(&self) -> StoneColor {
+        let state = self.state.lock().unwrap();
+        let props = state.root.properties.lock().unwrap();
+        let player = props.iter()
+            .find(|p| p.identifier == "PL")
+            .and_then(|p| p.values.first())
+            .and_then(|v| parse_color(v));
+        match player {
+            Some(color) => color,
+            None if handicap_of(&props) >= 2 => StoneColor::White,
+            None => StoneColor::Black,
+        }
+    }
+
+    /// Places `handicap` stones on the standard star points. See `set_free_handicap`.
+    pub fn set_fixed_handicap(&self, handicap: u32) -> Result<(), SgfError> {
+        let (width, height) = {
+            let state = self.state.lock().unwrap();
+            (state.width, state.height)
+        };
+        if width != height {
+            return Err(SgfError::ParseError { message: "Fixed handicap needs a square board".into() });
+        }
+        let points = handicap::fixed_handicap_points(width, handicap)?;
+        self.set_free_handicap(points)
+    }
+
+    /// Places handicap stones at arbitrary points before the first move.
+    /// Writes `HA` and `AB` at the root, and `Rules::handicap_komi` unless `KM` is
+    /// already set; White moves next.
+    pub fn set_free_handicap(&self, stones: Vec<Point>) -> Result<(), SgfError> {
+        let mut state = self.state.lock().unwrap();
+        if !state.root.children.lock().unwrap().is_empty() {
+            return Err(SgfError::ParseError { message: "Handicap must be set before the first move".into() });
+        }
+        if stones.len() < 2 {
+            return Err(SgfError::ParseError { message: "Handicap needs at least 2 stones".into() });
+        }
+
+        let mut coords = Vec::new();
+        for p in &stones {
+            if p.x >= state.width || p.y >= state.height {
+                return Err(SgfError::ParseError { message: "Out of bounds".into() });
+            }
+            let coord = sgf_coord(p.x, p.y);
+            if coords.contains(&coord) {
+                return Err(SgfError::ParseError { message: "Duplicate handicap stone".into() });
+            }
+            coords.push(coord);
+        }
+
+        {
+            let mut props = state.root.properties.lock().unwrap();
+            props.retain(|p| p.identifier != "AW" && p.identifier != "AE");
+            set_property(&mut props, "HA", vec![stones.len().to_string()]);
+            set_property(&mut props, "AB", coords);
+            if !props.iter().any(|p| p.identifier == "KM") {
+                set_property(&mut props, "KM", vec![state.rules.handicap_komi().to_string()]);
+            }
         }
-        // Default to Black for root or if no move property found
-        StoneColor::Black
+
+        state.board_cache.clear();
+        Ok(())
+    }
+
+    /// Records a pass (`B[]`/`W[]`) for `color`, or follows an existing pass child.
+    pub fn pass(&self, color: StoneColor) {
+        let mut state = self.state.lock().unwrap();
+        let (width, height) = (state.width, state.height);
+        let prop_id = match color {
+            StoneColor::Black => "B",
+            StoneColor::White => "W",
+        };
+
+        let existing_child = {
+            let children = state.current_node.children.lock().unwrap();
+            children.iter().find(|c| {
+                let props = c.properties.lock().unwrap();
+                props.iter().any(|p| {
+                    p.identifier == prop_id && p.values.first().is_some_and(|v| is_sgf_pass(v, width, height))
+                })
+            }).cloned()
+        };
+
+        let next_node = match existing_child {
+            Some(child) => child,
+            None => {
+                let new_board = state.current_board().pass();
+                let new_node = Arc::new(SgfNode {
+                    properties: Mutex::new(vec![SgfProperty {
+                        identifier: prop_id.to_string(),
+                        values: vec![String::new()],
+                    }]),
+                    children: Mutex::new(vec![]),
+                    source: vec![],
+                });
+                state.current_node.children.lock().unwrap().push(new_node.clone());
+                state.board_cache.insert(Arc::as_ptr(&new_node) as usize, new_board);
+                new_node
+            }
+        };
+
+        let current = state.current_node.clone();
+        state.history.push(current);
+        state.current_node = next_node;
+    }
+
+    /// Number of passes in a row ending at the current node.
+    /// Nodes without a move (comments, setup) do not break the streak.
+    pub fn get_consecutive_passes(&self) -> u32 {
+        let state = self.state.lock().unwrap();
+        let mut count = 0;
+        for node in state.path().iter().rev() {
+            let props = node.properties.lock().unwrap();
+            match props.iter().find(|p| p.identifier == "B" || p.identifier == "W") {
+                Some(mv) if mv.values.first().is_none_or(|v| is_sgf_pass(v, state.width, state.height)) => count += 1,
+                Some(_) => break,
+                None => continue,
+            }
+        }
+        count
+    }
+
+    /// The game has ended once both players pass in succession.
+    pub fn is_game_over(&self) -> bool {
+        self.get_consecutive_passes() >= 2
     }
 
     pub fn get_last_move(&self) -> Option<SgfProperty> {
@@ -690,9 +1175,8 @@ impl Game {
         moves
     }
 
-    pub fn get_initial_stones(&self) -> Vec<Vec<String>> {
+    pub fn get_initial_stones(&self) -> Result<Vec<Vec<String>>, SgfError> {
         let state = self.state.lock().unwrap();
-        let size = state.size;
         let mut stones = Vec::new();
 
         // Collect all AB/AW from the root node as initial stones
@@ -701,17 +1185,16 @@ impl Game {
             if prop.identifier == "AB" || prop.identifier == "AW" {
                 let color = if prop.identifier == "AB" { "B" } else { "W" };
                 for val in &prop.values {
-                    let gtp_move = sgf_to_gtp(val, size);
+                    let gtp_move = sgf_to_gtp(val, state.width, state.height)?;
                     stones.push(vec![color.to_string(), gtp_move]);
                 }
             }
         }
-        stones
+        Ok(stones)
     }
 
-    pub fn get_analysis_moves(&self) -> Vec<Vec<String>> {
+    pub fn get_analysis_moves(&self) -> Result<Vec<Vec<String>>, SgfError> {
         let state = self.state.lock().unwrap();
-        let size = state.size;
         let mut path = state.history.clone();
         path.push(state.current_node.clone());
 
@@ -721,33 +1204,27 @@ impl Game {
             for prop in props.iter() {
                 if prop.identifier == "B" || prop.identifier == "W" {
                     if let Some(val) = prop.values.first() {
-                        let gtp_move = sgf_to_gtp(val, size);
+                        let gtp_move = sgf_to_gtp(val, state.width, state.height)?;
                         moves.push(vec![prop.identifier.clone(), gtp_move]);
                     }
                 }
             }
         }
-        moves
+        Ok(moves)
     }
 
     pub fn get_current_board_stones(&self) -> Vec<Vec<String>> {
         let board = self.get_board();
-        let size = board.get_size();
+        let (width, height) = (board.get_width(), board.get_height());
         let mut stones = Vec::new();
-        for y in 0..size {
-            for x in 0..size {
+        for y in 0..height {
+            for x in 0..width {
                 if let Some(color) = board.get_stone(x, y) {
                     let color_str = match color {
                         StoneColor::Black => "B",
                         StoneColor::White => "W",
                     };
-                    let col = if x >= 8 {
-                        (b'A' + x as u8 + 1) as char
-                    } else {
-                        (b'A' + x as u8) as char
-                    };
-                    let row = size - y;
-                    let gtp_move = format!("{}{}", col, row);
+                    let gtp_move = point_to_gtp(x, y, height);
                     stones.push(vec![color_str.to_string(), gtp_move]);
                 }
             }
@@ -755,11 +1232,10 @@ impl Game {
         stones
     }
 
-    pub fn get_main_line_moves(&self) -> Vec<Vec<String>> {
+    pub fn get_main_line_moves(&self) -> Result<Vec<Vec<String>>, SgfError> {
         let state = self.state.lock().unwrap();
         let mut moves = Vec::new();
         let mut current = state.root.clone();
-        let size = state.size;
 
         loop {
             let children = current.get_children();
@@ -772,13 +1248,13 @@ impl Game {
             if let Some(move_prop) = props.iter().find(|p| p.identifier == "B" || p.identifier == "W") {
                 if let Some(coords) = move_prop.values.first() {
                     let color = move_prop.identifier.clone();
-                    let gtp_move = sgf_to_gtp(coords, size);
+                    let gtp_move = sgf_to_gtp(coords, state.width, state.height)?;
                     moves.push(vec![color, gtp_move]);
                 }
             }
             current = next;
         }
-        moves
+        Ok(moves)
     }
 
     pub fn can_go_back(&self) -> bool {
@@ -844,13 +1320,15 @@ impl Game {
     }
 
     pub fn place_stone(&self, x: u32, y: u32, color: StoneColor) -> Result<(), SgfError> {
+        self.play_move(x, y, color).map(|_| ())
+    }
+
+    /// Plays a move like `place_stone`, reporting which stones it captured.
+    pub fn play_move(&self, x: u32, y: u32, color: StoneColor) -> Result<MoveResult, SgfError> {
         let mut state = self.state.lock().unwrap();
 
         // 1. Check if this move already exists as a child
-        let coords = format!("{}{}",
-            (b'a' + x as u8) as char,
-            (b'a' + y as u8) as char
-        );
+        let coords = sgf_coord(x, y);
         let prop_id = match color {
             StoneColor::Black => "B",
             StoneColor::White => "W",
@@ -869,15 +1347,18 @@ impl Game {
             let current = state.current_node.clone();
             state.history.push(current);
             state.current_node = child;
-            return Ok(());
+            let board = state.current_board();
+            let captured = board.get_last_captures();
+            return Ok(MoveResult { board, captured });
         }
 
         // 2. Create new move
-        let current_board = state.board_cache.get(&(Arc::as_ptr(&state.current_node) as usize))
-            .cloned()
-            .unwrap_or_else(|| Board::new(state.size));
-
-        let new_board = current_board.place_stone(x, y, color)?;
+        let current_board = state.current_board();
+        let result = current_board.play(x, y, color)?;
+        let new_board = result.board.clone();
+        if state.violates_superko(&new_board, color) {
+            return Err(Legality::Superko.into_error());
+        }
 
         let new_node = Arc::new(SgfNode {
             properties: Mutex::new(vec![SgfProperty {
@@ -885,6 +1366,7 @@ impl Game {
                 values: vec![coords],
             }]),
             children: Mutex::new(vec![]),
+            source: vec![],
         });
 
         // Attach to tree
@@ -896,10 +1378,93 @@ impl Game {
         state.current_node = new_node.clone();
         state.board_cache.insert(Arc::as_ptr(&new_node) as usize, new_board);
 
-        Ok(())
+        Ok(result)
+    }
+}
+
+/// Replaces the values of property `id`, appending the property if it is missing.
+fn set_property(props: &mut Vec<SgfProperty>, id: &str, values: Vec<String>) {
+    if let Some(p) = props.iter_mut().find(|p| p.identifier == id) {
+        p.values = values;
+    } else {
+        props.push(SgfProperty {
+            identifier: id.to_string(),
+            values,
+        });
+    }
+}
+
+impl Game {
+    /// Opens the game stored under `root`, positioned at the root.
+    fn from_root(root: Arc<SgfNode>) -> Arc<Self> {
+        // Try to find size; a missing or unusable SZ means 19x19
+        let (width, height) = size_of(&root);
+
+        // Unknown or missing RU falls back to the default (Chinese) rules
+        let rules = {
+            let props = root.properties.lock().unwrap();
+            props.iter()
+                .find(|p| p.identifier == "RU")
+                .and_then(|p| p.values.first())
+                .and_then(|v| Rules::from_sgf(v))
+                .unwrap_or_default()
+        };
+
+        // The root may carry setup stones, so its board is computed lazily like any other node.
+        let board_cache = std::collections::HashMap::new();
+
+        Arc::new(Self {
+            state: Mutex::new(GameState {
+                root: root.clone(),
+                current_node: root,
+                history: vec![],
+                board_cache,
+                width,
+                height,
+                rules,
+            }),
+        })
     }
 }
 
+/// Board size from the root's `SZ`; a missing or unusable value means 19x19.
+fn size_of(root: &SgfNode) -> (u32, u32) {
+    let props = root.properties.lock().unwrap();
+    props.iter()
+        .find(|p| p.identifier == "SZ")
+        .and_then(|p| p.values.first())
+        .and_then(|v| parse_board_size(v))
+        .unwrap_or((19, 19))
+}
+
+fn metadata_of(root: &SgfNode, width: u32, height: u32) -> GameMetadata {
+    let props = root.properties.lock().unwrap();
+
+    let mut meta = GameMetadata {
+        size: width,
+        width,
+        height,
+        ..Default::default()
+    };
+
+    for p in props.iter() {
+        match p.identifier.as_str() {
+            "PB" => meta.black_name = p.values.first().cloned().unwrap_or_default(),
+            "BR" => meta.black_rank = p.values.first().cloned().unwrap_or_default(),
+            "PW" => meta.white_name = p.values.first().cloned().unwrap_or_default(),
+            "WR" => meta.white_rank = p.values.first().cloned().unwrap_or_default(),
+            "KM" => meta.komi = p.values.first().and_then(|v| v.parse().ok()).unwrap_or(0.0),
+            "RE" => meta.result = p.values.first().cloned().unwrap_or_default(),
+            "DT" => meta.date = p.values.first().cloned().unwrap_or_default(),
+            "EV" => meta.event = p.values.first().cloned().unwrap_or_default(),
+            "GN" => meta.game_name = p.values.first().cloned().unwrap_or_default(),
+            "PC" => meta.place = p.values.first().cloned().unwrap_or_default(),
+            _ => {}
+        }
+    }
+    meta
+}
+
 fn find_path(current: &Arc<SgfNode>, target: &Arc<SgfNode>) -> Option<Vec<Arc<SgfNode>>> {
     if Arc::ptr_eq(current, target) {
         return Some(vec![]);
@@ -921,7 +1486,7 @@ fn get_max_depth(node: &Arc<SgfNode>) -> u32 {
     if children.is_empty() {
         0
     } else {
-        1 + children.iter().map(|c| get_max_depth(c)).max().unwrap_or(0)
+        1 + children.iter().map(get_max_depth).max().unwrap_or(0)
     }
 }
 
@@ -987,6 +1552,21 @@ impl GtpEngine {
         result.map_err(|e| SgfError::ParseError { message: e.to_string() })
     }
 
+    /// Asks the engine for its preferred free handicap placement on an empty board
+    /// (KataGo answers with its optimal handicap positions).
+    pub async fn place_free_handicap(&self, size: u32, handicap: u32) -> Result<Vec<Point>, SgfError> {
+        self.send_command(format!("boardsize {}", size)).await?;
+        self.send_command("clear_board".to_string()).await?;
+        let response = self.send_command(format!("place_free_handicap {}", handicap)).await?;
+        response
+            .split_whitespace()
+            .map(|v| match parse_move(v.to_string(), Notation::Gtp, size, size)? {
+                Move::Play { point } => Ok(point),
+                Move::Pass => Err(SgfError::ParseError { message: "Engine returned a pass as handicap".into() }),
+            })
+            .collect()
+    }
+
     pub async fn stop(&self) -> Result<(), SgfError> {
         let mut lock = self.client.lock().await;
         if let Some(client) = lock.take() {
@@ -0,0 +1,202 @@
+use std::collections::HashSet;
+
+use crate::{Board, Group, Point, StoneColor};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum ChainStatus {
+    /// Cannot be captured even if the owner passes every move (Benson's algorithm).
+    UnconditionallyAlive,
+    /// Shares liberties with enemy chains that neither side can fill without self-atari.
+    Seki,
+    /// Anything else; life and death has to be read out or judged by an engine.
+    Undecided,
+}
+
+#[derive(uniffi::Record, Clone, Debug)]
+pub struct ChainLife {
+    pub group: Group,
+    pub status: ChainStatus,
+}
+
+#[uniffi::export]
+impl Board {
+    /// Classifies every chain on the board, ordered by its first stone.
+    pub fn analyze_life(&self) -> Vec<ChainLife> {
+        let alive = self.unconditionally_alive();
+        let seki = self.seki_chains(&alive);
+        self.chain_heads()
+            .into_iter()
+            .map(|h| ChainLife {
+                group: self.group_at(h),
+                status: if alive.contains(&h) {
+                    ChainStatus::UnconditionallyAlive
+                } else if seki.contains(&h) {
+                    ChainStatus::Seki
+                } else {
+                    ChainStatus::Undecided
+                },
+            })
+            .collect()
+    }
+
+    /// Status of the chain at (x, y), or `None` for an empty point.
+    pub fn get_chain_status(&self, x: u32, y: u32) -> Option<ChainStatus> {
+        self.get_stone(x, y)?;
+        let head = self.chains.head((y * self.width + x) as usize);
+        let alive = self.unconditionally_alive();
+        Some(if alive.contains(&head) {
+            ChainStatus::UnconditionallyAlive
+        } else if self.seki_chains(&alive).contains(&head) {
+            ChainStatus::Seki
+        } else {
+            ChainStatus::Undecided
+        })
+    }
+
+    /// Liberties of chains in seki: the empty points that belong to neither side.
+    pub fn get_seki_points(&self) -> Vec<Point> {
+        let mut points: Vec<usize> = self
+            .seki_chains(&self.unconditionally_alive())
+            .into_iter()
+            .flat_map(|h| self.liberties(h))
+            .collect();
+        points.sort_unstable();
+        points.dedup();
+        points.into_iter().map(|i| self.point(i)).collect()
+    }
+}
+
+impl Board {
+    /// Head stone of every chain, in grid order of the heads' first stones.
+    fn chain_heads(&self) -> Vec<usize> {
+        let mut seen = HashSet::new();
+        (0..self.grid.len())
+            .filter(|&i| self.grid[i].is_some() && seen.insert(self.chains.head(i)))
+            .map(|i| self.chains.head(i))
+            .collect()
+    }
+
+    /// Heads of the chains of either color that Benson's algorithm proves alive.
+    pub(crate) fn unconditionally_alive(&self) -> HashSet<usize> {
+        let mut alive = HashSet::new();
+        for color in [StoneColor::Black, StoneColor::White] {
+            alive.extend(self.benson(color));
+        }
+        alive
+    }
+
+    fn benson(&self, color: StoneColor) -> HashSet<usize> {
+        // Regions: maximal connected areas of points not occupied by `color`
+        let mut region_of = vec![usize::MAX; self.grid.len()];
+        let mut regions: Vec<(Vec<usize>, HashSet<usize>)> = Vec::new(); // (empty points, bordering chains)
+        for start in 0..self.grid.len() {
+            if self.grid[start] == Some(color) || region_of[start] != usize::MAX {
+                continue;
+            }
+            let id = regions.len();
+            let mut empty = Vec::new();
+            let mut border = HashSet::new();
+            let mut stack = vec![start];
+            region_of[start] = id;
+            while let Some(i) = stack.pop() {
+                if self.grid[i].is_none() {
+                    empty.push(i);
+                }
+                for n in self.adjacent(i) {
+                    if self.grid[n] == Some(color) {
+                        border.insert(self.chains.head(n));
+                    } else if region_of[n] == usize::MAX {
+                        region_of[n] = id;
+                        stack.push(n);
+                    }
+                }
+            }
+            regions.push((empty, border));
+        }
+
+        // A region is vital to a chain when all of its empty points are liberties of that chain
+        let vital = |(empty, border): &(Vec<usize>, HashSet<usize>), head: usize| {
+            !empty.is_empty()
+                && border.contains(&head)
+                && empty.iter().all(|&e| self.adjacent(e).any(|n| {
+                    self.grid[n] == Some(color) && self.chains.head(n) == head
+                }))
+        };
+
+        let mut chains: HashSet<usize> = self
+            .chain_heads()
+            .into_iter()
+            .filter(|&h| self.grid[h] == Some(color))
+            .collect();
+        let mut live_regions: Vec<usize> = (0..regions.len()).collect();
+        loop {
+            let before = (chains.len(), live_regions.len());
+            chains.retain(|&h| live_regions.iter().filter(|&&r| vital(&regions[r], h)).count() >= 2);
+            live_regions.retain(|&r| regions[r].1.iter().all(|h| chains.contains(h)));
+            if (chains.len(), live_regions.len()) == before {
+                return chains;
+            }
+        }
+    }
+
+    /// Heads of chains in seki. A chain qualifies when it is not unconditionally alive, has
+    /// at least two liberties, shares a liberty with an enemy chain, the opponent cannot fill
+    /// any of its liberties without self-atari, and neither side can fill a shared liberty
+    /// without self-atari. Every enemy chain it shares liberties with must qualify as well.
+    pub(crate) fn seki_chains(&self, alive: &HashSet<usize>) -> HashSet<usize> {
+        let mut partners: Vec<(usize, Vec<usize>)> = Vec::new();
+        for head in self.chain_heads() {
+            let Some(color) = self.grid[head] else { continue };
+            let liberties = self.liberties(head);
+            if alive.contains(&head) || liberties.len() < 2 {
+                continue;
+            }
+            let mut enemies = Vec::new();
+            let mut stable = true;
+            for &lib in &liberties {
+                let shared: Vec<usize> = self
+                    .adjacent(lib)
+                    .filter(|&n| self.grid[n] == Some(color.opponent()))
+                    .map(|n| self.chains.head(n))
+                    .collect();
+                if !self.fill_is_self_atari(lib, color.opponent())
+                    || (!shared.is_empty() && !self.fill_is_self_atari(lib, color))
+                {
+                    stable = false;
+                    break;
+                }
+                enemies.extend(shared);
+            }
+            if stable && !enemies.is_empty() {
+                enemies.sort_unstable();
+                enemies.dedup();
+                partners.push((head, enemies));
+            }
+        }
+
+        let mut seki: HashSet<usize> = partners.iter().map(|(h, _)| *h).collect();
+        loop {
+            let before = seki.len();
+            for (head, enemies) in &partners {
+                if !enemies.iter().all(|e| seki.contains(e)) {
+                    seki.remove(head);
+                }
+            }
+            if seki.len() == before {
+                return seki;
+            }
+        }
+    }
+
+    /// Whether `color` playing at `idx` is illegal, or captures nothing and leaves the
+    /// played chain with at most one liberty.
+    fn fill_is_self_atari(&self, idx: usize, color: StoneColor) -> bool {
+        let p = self.point(idx);
+        match self.try_play(p.x, p.y, color) {
+            // Multi-stone suicide under rules that allow it
+            Ok(result) if result.board.grid[idx].is_none() => true,
+            Ok(result) => result.captured.is_empty() && result.board.liberties(idx).len() <= 1,
+            Err(_) => true,
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use std::sync::Arc;
+
+use crate::properties::{raw_property, typed_property};
+use crate::{set_property, Game, Label, MarkupShape, Point, Property, Segment, SgfError, SgfNode, SgfProperty};
+
+/// How `toggle_label` numbers new labels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum LabelStyle {
+    /// A, B, ... Z, then AA, AB, ...
+    Letters,
+    /// 1, 2, 3, ...
+    Numbers,
+}
+
+#[derive(uniffi::Record, Clone, Debug, PartialEq)]
+pub struct Mark {
+    pub point: Point,
+    pub shape: MarkupShape,
+}
+
+/// Markup shown at the current node.
+#[derive(uniffi::Record, Clone, Debug, Default, PartialEq)]
+pub struct NodeMarkup {
+    /// Circles, crosses, squares, triangles and selections, top to bottom and left to right.
+    pub marks: Vec<Mark>,
+    pub labels: Vec<Label>,
+    pub arrows: Vec<Segment>,
+    pub lines: Vec<Segment>,
+    /// Dimmed points in effect; DD carries over to later nodes until another node sets it.
+    pub dimmed: Vec<Point>,
+}
+
+/// Identifiers of every markup property.
+const MARKUP_IDS: [&str; 9] = ["CR", "MA", "SQ", "TR", "SL", "DD", "LB", "AR", "LN"];
+
+/// Shapes of which a point may carry at most one.
+const EXCLUSIVE: [MarkupShape; 5] = [
+    MarkupShape::Circle,
+    MarkupShape::Cross,
+    MarkupShape::Square,
+    MarkupShape::Triangle,
+    MarkupShape::Selected,
+];
+
+#[uniffi::export]
+impl Game {
+    /// Adds or removes a shape at (x, y) on the current node. Returns whether the point
+    /// carries the shape afterwards. Placing a circle, cross, square, triangle or selection
+    /// replaces any other of those shapes at the point.
+    pub fn toggle_markup(&self, x: u32, y: u32, shape: MarkupShape) -> Result<bool, SgfError> {
+        let (node, width, height, path) = self.markup_target();
+        let point = checked_point(x, y, width, height)?;
+        let mut props = node.properties.lock().unwrap();
+
+        if shape == MarkupShape::Dimmed {
+            let inherited = inherited_dimming(&path, width, height);
+            let mut dimmed = shape_points(&props, MarkupShape::Dimmed, width, height)?.unwrap_or(inherited.clone());
+            let added = toggle_item(&mut dimmed, point);
+            dimmed.sort_by_key(|p| (p.y, p.x));
+            if dimmed == inherited {
+                remove_property(&mut props, "DD");
+            } else {
+                store(&mut props, Property::Markup { shape, points: dimmed });
+            }
+            return Ok(added);
+        }
+
+        let mut points = shape_points(&props, shape, width, height)?.unwrap_or_default();
+        let added = toggle_item(&mut points, point);
+        if added {
+            for other in EXCLUSIVE.into_iter().filter(|&s| s != shape) {
+                if let Some(mut others) = shape_points(&props, other, width, height)? {
+                    others.retain(|&p| p != point);
+                    store(&mut props, Property::Markup { shape: other, points: others });
+                }
+            }
+        }
+        points.sort_by_key(|p| (p.y, p.x));
+        store(&mut props, Property::Markup { shape, points });
+        Ok(added)
+    }
+
+    /// Removes the label at (x, y) and returns `None`, or adds the first label of `style`
+    /// not yet used on the current node and returns its text.
+    pub fn toggle_label(&self, x: u32, y: u32, style: LabelStyle) -> Result<Option<String>, SgfError> {
+        let (node, width, height, _) = self.markup_target();
+        let point = checked_point(x, y, width, height)?;
+        let mut props = node.properties.lock().unwrap();
+        let mut labels = node_labels(&props, width, height)?;
+        if let Some(i) = labels.iter().position(|l| l.point == point) {
+            labels.remove(i);
+            store(&mut props, Property::Labels { labels });
+            return Ok(None);
+        }
+        let text = (1..)
+            .map(|n| label_text(n, style))
+            .find(|t| labels.iter().all(|l| &l.text != t))
+            .unwrap();
+        labels.push(Label { point, text: text.clone() });
+        store(&mut props, Property::Labels { labels });
+        Ok(Some(text))
+    }
+
+    /// Sets the label at (x, y) on the current node; empty text removes it.
+    pub fn set_label(&self, x: u32, y: u32, text: String) -> Result<(), SgfError> {
+        let (node, width, height, _) = self.markup_target();
+        let point = checked_point(x, y, width, height)?;
+        let mut props = node.properties.lock().unwrap();
+        let mut labels = node_labels(&props, width, height)?;
+        labels.retain(|l| l.point != point);
+        if !text.is_empty() {
+            labels.push(Label { point, text });
+        }
+        store(&mut props, Property::Labels { labels });
+        Ok(())
+    }
+
+    /// Adds or removes an arrow from `from` to `to` on the current node.
+    /// Returns whether the arrow is present afterwards.
+    pub fn toggle_arrow(&self, from: Point, to: Point) -> Result<bool, SgfError> {
+        self.toggle_segment("AR", from, to)
+    }
+
+    /// Adds or removes a line between two points on the current node; lines have no direction.
+    /// Returns whether the line is present afterwards.
+    pub fn toggle_line(&self, from: Point, to: Point) -> Result<bool, SgfError> {
+        self.toggle_segment("LN", from, to)
+    }
+
+    /// All markup at the current node, with coordinates resolved for this board.
+    pub fn get_markup(&self) -> NodeMarkup {
+        let (node, width, height, history) = self.markup_target();
+        node_markup(&node, &history, width, height)
+    }
+
+    /// Removes every markup property from the current node, including its own DD.
+    pub fn clear_markup(&self) {
+        let (node, ..) = self.markup_target();
+        node.properties.lock().unwrap().retain(|p| !MARKUP_IDS.contains(&p.identifier.as_str()));
+    }
+}
+
+impl Game {
+    /// Current node, board size, and the nodes above the current one from the root down.
+    fn markup_target(&self) -> (Arc<SgfNode>, u32, u32, Vec<Arc<SgfNode>>) {
+        let state = self.state.lock().unwrap();
+        (state.current_node.clone(), state.width, state.height, state.history.clone())
+    }
+
+    fn toggle_segment(&self, id: &str, from: Point, to: Point) -> Result<bool, SgfError> {
+        let (node, width, height, _) = self.markup_target();
+        checked_point(from.x, from.y, width, height)?;
+        checked_point(to.x, to.y, width, height)?;
+        if from == to {
+            return Err(SgfError::ParseError { message: "Segment needs two different points".to_string() });
+        }
+        let mut props = node.properties.lock().unwrap();
+        let mut segments = match find_typed(&props, id, width, height) {
+            None => vec![],
+            Some(Property::Arrows { segments } | Property::Lines { segments }) => segments,
+            Some(_) => return Err(unreadable(id)),
+        };
+        let same = |s: &Segment| {
+            (s.from == from && s.to == to) || (id == "LN" && s.from == to && s.to == from)
+        };
+        let added = match segments.iter().position(same) {
+            Some(i) => {
+                segments.remove(i);
+                false
+            }
+            None => {
+                segments.push(Segment { from, to });
+                true
+            }
+        };
+        store(
+            &mut props,
+            if id == "AR" { Property::Arrows { segments } } else { Property::Lines { segments } },
+        );
+        Ok(added)
+    }
+}
+
+fn checked_point(x: u32, y: u32, width: u32, height: u32) -> Result<Point, SgfError> {
+    if x < width && y < height {
+        Ok(Point { x, y })
+    } else {
+        Err(SgfError::ParseError { message: "Out of bounds".to_string() })
+    }
+}
+
+fn unreadable(id: &str) -> SgfError {
+    SgfError::ParseError { message: format!("Unreadable {} property", id) }
+}
+
+fn find_typed(props: &[SgfProperty], id: &str, width: u32, height: u32) -> Option<Property> {
+    props.iter().find(|p| p.identifier == id).map(|p| typed_property(p.clone(), width, height))
+}
+
+/// Points of `shape` on the node, or `None` when the node does not have the property.
+fn shape_points(
+    props: &[SgfProperty],
+    shape: MarkupShape,
+    width: u32,
+    height: u32,
+) -> Result<Option<Vec<Point>>, SgfError> {
+    let id = raw_property(Property::Markup { shape, points: vec![] }).identifier;
+    match find_typed(props, &id, width, height) {
+        None => Ok(None),
+        Some(Property::Markup { points, .. }) => Ok(Some(points)),
+        Some(_) => Err(unreadable(&id)),
+    }
+}
+
+fn node_labels(props: &[SgfProperty], width: u32, height: u32) -> Result<Vec<Label>, SgfError> {
+    match find_typed(props, "LB", width, height) {
+        None => Ok(vec![]),
+        Some(Property::Labels { labels }) => Ok(labels),
+        Some(_) => Err(unreadable("LB")),
+    }
+}
+/// Markup of `node`, with dimming inherited from `history`, the nodes above it.
+pub(crate) fn node_markup(node: &SgfNode, history: &[Arc<SgfNode>], width: u32, height: u32) -> NodeMarkup {
+    let mut markup = NodeMarkup::default();
+    let mut own_dimming = None;
+    for prop in node.get_typed_properties(width, height) {
+        match prop {
+            Property::Markup { shape: MarkupShape::Dimmed, points } => own_dimming = Some(points),
+            Property::Markup { shape, points } => {
+                markup.marks.extend(points.into_iter().map(|point| Mark { point, shape }))
+            }
+            Property::Labels { labels } => markup.labels.extend(labels),
+            Property::Arrows { segments } => markup.arrows.extend(segments),
+            Property::Lines { segments } => markup.lines.extend(segments),
+            _ => {}
+        }
+    }
+    markup.marks.sort_by_key(|m| (m.point.y, m.point.x));
+    markup.dimmed = own_dimming.unwrap_or_else(|| inherited_dimming(history, width, height));
+    markup
+}
+
+/// DD in effect at the current node before it sets its own: the nearest ancestor's.
+fn inherited_dimming(path: &[Arc<SgfNode>], width: u32, height: u32) -> Vec<Point> {
+    path.iter()
+        .rev()
+        .find_map(|node| {
+            node.get_typed_properties(width, height).into_iter().find_map(|p| match p {
+                Property::Markup { shape: MarkupShape::Dimmed, points } => Some(points),
+                _ => None,
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Removes `item` if present, otherwise appends it. Returns whether it was added.
+fn toggle_item<T: PartialEq>(items: &mut Vec<T>, item: T) -> bool {
+    match items.iter().position(|i| *i == item) {
+        Some(i) => {
+            items.remove(i);
+            false
+        }
+        None => {
+            items.push(item);
+            true
+        }
+    }
+}
+
+/// Writes a markup property, dropping it when it has nothing left (except DD, where an
+/// empty list is meaningful).
+fn store(props: &mut Vec<SgfProperty>, property: Property) {
+    let empty = match &property {
+        Property::Markup { shape: MarkupShape::Dimmed, .. } => false,
+        Property::Markup { points, .. } => points.is_empty(),
+        Property::Labels { labels } => labels.is_empty(),
+        Property::Arrows { segments } | Property::Lines { segments } => segments.is_empty(),
+        _ => false,
+    };
+    let raw = raw_property(property);
+    if empty {
+        remove_property(props, &raw.identifier);
+    } else {
+        set_property(props, &raw.identifier, raw.values);
+    }
+}
+
+fn remove_property(props: &mut Vec<SgfProperty>, id: &str) {
+    props.retain(|p| p.identifier != id);
+}
+
+fn label_text(n: u32, style: LabelStyle) -> String {
+    match style {
+        LabelStyle::Numbers => n.to_string(),
+        LabelStyle::Letters => {
+            // Bijective base 26: 1 -> A, 26 -> Z, 27 -> AA
+            let mut n = n;
+            let mut text = Vec::new();
+            while n > 0 {
+                n -= 1;
+                text.push(b'A' + (n % 26) as u8);
+                n /= 26;
+            }
+            text.reverse();
+            String::from_utf8(text).unwrap()
+        }
+    }
+}
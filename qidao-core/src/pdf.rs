@@ -0,0 +1,414 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, TextStr};
+
+use crate::diagram::{escape, numbered_move, Layout, Numbering};
+use crate::raster::svg_tree;
+use crate::{print_diagram_theme, DiagramOptions, Game, GameMetadata, MoveRange, SgfError, SgfNode, StoneStyle};
+
+/// Page margin, in points.
+const MARGIN: f64 = 42.0;
+/// Space between diagram slots, in points.
+const GAP: f64 = 18.0;
+const CAPTION_SIZE: f64 = 10.0;
+const COMMENT_SIZE: f64 = 9.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum PageSize {
+    A4,
+    A5,
+    B5,
+    Letter,
+}
+
+impl PageSize {
+    /// Width and height in points, portrait.
+    fn points(self) -> (f64, f64) {
+        match self {
+            PageSize::A4 => (595.28, 841.89),
+            PageSize::A5 => (419.53, 595.28),
+            PageSize::B5 => (498.90, 708.66),
+            PageSize::Letter => (612.0, 792.0),
+        }
+    }
+}
+
+#[derive(uniffi::Record, Clone, Debug, PartialEq)]
+pub struct KifuOptions {
+    /// Board drawing; each diagram draws its own moves whatever `move_range` says, and
+    /// `markup` is not drawn.
+    pub diagram: DiagramOptions,
+    pub page_size: PageSize,
+    /// Main-line moves per diagram. A move whose `MN` renumbers the game starts a new one.
+    pub moves_per_diagram: u32,
+    /// Diagrams are laid out in two columns from 2 per page on.
+    pub diagrams_per_page: u32,
+    /// Numbers every main-line diagram from 1; the caption keeps the game's move numbers.
+    /// Variation diagrams always count from 1.
+    pub restart_numbering: bool,
+    /// Adds a diagram for each side branch of the main line, after the diagram it leaves.
+    pub variations: bool,
+    /// Prints node comments under the diagram showing their move.
+    pub comments: bool,
+}
+
+impl Default for KifuOptions {
+    fn default() -> Self {
+        KifuOptions {
+            diagram: DiagramOptions {
+                markup: false,
+                mark_last_move: false,
+                stone_style: StoneStyle::Flat,
+                theme: print_diagram_theme(),
+                ..DiagramOptions::default()
+            },
+            page_size: PageSize::A4,
+            moves_per_diagram: 50,
+            diagrams_per_page: 2,
+            restart_numbering: false,
+            variations: true,
+            comments: true,
+        }
+    }
+}
+
+/// A diagram of the kifu as `to_pdf` lays it out, for listing or previewing them.
+#[derive(uniffi::Record, Clone, Debug, PartialEq)]
+pub struct KifuFigure {
+    pub caption: String,
+    /// Comments printed under the diagram, labelled with the move numbers shown on it.
+    pub comments: Vec<String>,
+}
+
+#[uniffi::export]
+pub fn default_kifu_options() -> KifuOptions {
+    KifuOptions::default()
+}
+
+#[uniffi::export]
+impl Game {
+    /// Lays the game out as a printable kifu: a game-info header, the main line in diagrams
+    /// of `options.moves_per_diagram` moves with their comments, and the side branches of
+    /// the main line as variation diagrams. The current node is unchanged afterwards.
+    pub fn to_pdf(&self, options: KifuOptions) -> Result<Vec<u8>, SgfError> {
+        let metadata = self.get_metadata();
+        let figures = figures(&self.get_root_node(), &metadata, &options);
+        let pages = paginate(self, &figures, &metadata, &options);
+        write_pdf(&pages, options.page_size, &title(&metadata))
+    }
+
+    /// The diagrams `to_pdf` prints for `options`, in order.
+    pub fn kifu_figures(&self, options: KifuOptions) -> Vec<KifuFigure> {
+        figures(&self.get_root_node(), &self.get_metadata(), &options)
+            .into_iter()
+            .map(|f| KifuFigure { caption: f.caption, comments: f.comments })
+            .collect()
+    }
+}
+
+/// One diagram of the booklet: the last `moves` moves on `path`, the line from the root
+/// to the position it ends at, numbered `range` in the game.
+struct Figure {
+    caption: String,
+    path: Vec<Arc<SgfNode>>,
+    moves: u32,
+    range: Option<MoveRange>,
+    restart: bool,
+    comments: Vec<String>,
+}
+
+/// Nodes of a line with the number of the last move played by each.
+type Line = Vec<(Arc<SgfNode>, u32)>;
+
+/// Main-line diagrams, each followed by the variations branching off within it.
+fn figures(root: &Arc<SgfNode>, metadata: &GameMetadata, options: &KifuOptions) -> Vec<Figure> {
+    let (width, height) = (metadata.width, metadata.height);
+    let per_diagram = options.moves_per_diagram.max(1);
+    let mut main: Vec<(Figure, Line)> = Vec::new();
+    let mut number = 0;
+    let mut node = Some(root.clone());
+    while let Some(current) = node {
+        let played = numbered_move(&current, width, height, number).map(|(n, ..)| n);
+        // Each diagram holds one run of numbers, so its caption and stones agree
+        let full = main
+            .last()
+            .is_some_and(|(f, _)| f.moves == per_diagram || (f.moves > 0 && played != Some(number + 1)));
+        if let Some(n) = played {
+            number = n;
+        }
+        if main.is_empty() || (played.is_some() && full) {
+            let figure = Figure {
+                caption: format!("Diagram {}", main.len() + 1),
+                path: vec![],
+                moves: 0,
+                range: None,
+                restart: options.restart_numbering,
+                comments: vec![],
+            };
+            main.push((figure, vec![]));
+        }
+        let (figure, branch_points) = main.last_mut().unwrap();
+        if let Some(n) = played {
+            figure.moves += 1;
+            figure.range = Some(MoveRange { first: figure.range.map_or(n, |r| r.first), last: n });
+        }
+        if options.comments {
+            let label = played.map(|n| if figure.restart { figure.moves } else { n });
+            figure.comments.extend(comment(&current, label));
+        }
+        branch_points.push((current.clone(), number));
+        node = current.get_children().first().cloned();
+    }
+
+    let mut figures = Vec::new();
+    let mut variations = 0;
+    let mut line = Vec::new();
+    for (mut figure, branch_points) in main {
+        if let Some(range) = figure.range {
+            let _ = write!(figure.caption, " ({}\u{2013}{})", range.first, range.last);
+        }
+        let above = line.len();
+        line.extend(branch_points.iter().map(|(node, _)| node.clone()));
+        figure.path = line.clone();
+        figures.push(figure);
+        for (i, (parent, number)) in branch_points.iter().enumerate().filter(|_| options.variations) {
+            for child in parent.get_children().iter().skip(1) {
+                let parents = &line[..=above + i];
+                if let Some(variation) = variation(parents, child, *number, variations + 1, metadata, options) {
+                    variations += 1;
+                    figures.push(variation);
+                }
+            }
+        }
+    }
+    figures
+}
+
+/// The branch starting at `first` below `parents` and following first children, numbered
+/// from 1; `None` when it plays no move.
+fn variation(
+    parents: &[Arc<SgfNode>],
+    first: &Arc<SgfNode>,
+    previous: u32,
+    index: usize,
+    metadata: &GameMetadata,
+    options: &KifuOptions,
+) -> Option<Figure> {
+    let mut figure =
+        Figure { caption: String::new(), path: parents.to_vec(), moves: 0, range: None, restart: true, comments: vec![] };
+    let mut number = previous;
+    let mut node = Some(first.clone());
+    while let Some(current) = node {
+        let played = numbered_move(&current, metadata.width, metadata.height, number).map(|(n, ..)| n);
+        if let Some(n) = played {
+            number = n;
+            figure.moves += 1;
+            figure.range = Some(MoveRange { first: figure.range.map_or(n, |r| r.first), last: n });
+        }
+        if options.comments {
+            figure.comments.extend(comment(&current, played.map(|_| figure.moves)));
+        }
+        figure.path.push(current.clone());
+        node = current.get_children().first().cloned();
+    }
+    let range = figure.range?;
+    figure.caption = format!("Variation {} (1 = move {})", index, range.first);
+    Some(figure)
+}
+
+/// The node's comment, labelled with the number its move shows on the diagram.
+fn comment(node: &SgfNode, label: Option<u32>) -> Option<String> {
+    let text = node.get_comment();
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some(match label {
+        Some(n) => format!("{}: {}", n, text),
+        None => text.to_string(),
+    })
+}
+
+/// Draws every page as an SVG document in points.
+fn paginate(game: &Game, figures: &[Figure], metadata: &GameMetadata, options: &KifuOptions) -> Vec<String> {
+    let (page_width, page_height) = options.page_size.points();
+    let per_page = options.diagrams_per_page.max(1) as usize;
+    let columns = if per_page >= 2 { 2 } else { 1 };
+    let rows = per_page.div_ceil(columns);
+    let slot_width = (page_width - 2.0 * MARGIN - GAP * (columns - 1) as f64) / columns as f64;
+    let font = escape(&options.diagram.theme.font_family);
+    let text_color = &options.diagram.theme.coordinates;
+
+    let chunks: Vec<&[Figure]> = if figures.is_empty() { vec![&[]] } else { figures.chunks(per_page).collect() };
+    let total = chunks.len();
+    let mut pages = Vec::new();
+    for (page, chunk) in chunks.into_iter().enumerate() {
+        let mut svg = String::new();
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0:.2}" height="{1:.2}" viewBox="0 0 {0:.2} {1:.2}" font-family="{2}" fill="{3}"><rect width="100%" height="100%" fill="white"/>"#,
+            page_width, page_height, font, text_color
+        );
+        let mut top = MARGIN;
+        if page == 0 {
+            top = header(&mut svg, metadata, page_width);
+        }
+        let bottom = page_height - MARGIN;
+        let slot_height = (bottom - top - GAP * (rows - 1) as f64) / rows as f64;
+        for (i, figure) in chunk.iter().enumerate() {
+            let x = MARGIN + (i % columns) as f64 * (slot_width + GAP);
+            let y = top + (i / columns) as f64 * (slot_height + GAP);
+            slot(&mut svg, game, figure, options, x, y, slot_width, slot_height);
+        }
+        let _ = write!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" font-size="8" text-anchor="middle">{} / {}</text></svg>"#,
+            page_width / 2.0,
+            page_height - MARGIN / 2.0,
+            page + 1,
+            total
+        );
+        pages.push(svg);
+    }
+    pages
+}
+
+/// Title, players and game details; returns where the diagrams start.
+fn header(svg: &mut String, metadata: &GameMetadata, page_width: f64) -> f64 {
+    let player = |color: &str, name: &str, rank: &str| {
+        let name = if name.is_empty() { "?" } else { name };
+        if rank.is_empty() {
+            format!("{} {}", color, name)
+        } else {
+            format!("{} {} ({})", color, name, rank)
+        }
+    };
+    let players = format!(
+        "{}    {}",
+        player("Black", &metadata.black_name, &metadata.black_rank),
+        player("White", &metadata.white_name, &metadata.white_rank)
+    );
+    let mut details = vec![format!("Komi {}", metadata.komi)];
+    if !metadata.result.is_empty() {
+        details.push(format!("Result {}", metadata.result));
+    }
+    details.extend([&metadata.date, &metadata.event, &metadata.place].into_iter().filter(|s| !s.is_empty()).cloned());
+
+    let center = page_width / 2.0;
+    let mut y = MARGIN + 16.0;
+    let _ = write!(svg, r#"<text x="{:.1}" y="{:.1}" font-size="16" font-weight="bold" text-anchor="middle">{}</text>"#, center, y, escape(&title(metadata)));
+    for line in [players, details.join("  \u{00b7}  ")] {
+        y += 16.0;
+        let _ = write!(svg, r#"<text x="{:.1}" y="{:.1}" font-size="10" text-anchor="middle">{}</text>"#, center, y, escape(&line));
+    }
+    y + GAP
+}
+
+fn title(metadata: &GameMetadata) -> String {
+    if !metadata.game_name.is_empty() {
+        metadata.game_name.clone()
+    } else if !metadata.black_name.is_empty() || !metadata.white_name.is_empty() {
+        format!("{} \u{2013} {}", metadata.black_name, metadata.white_name)
+    } else {
+        "Game record".to_string()
+    }
+}
+
+/// One diagram with its caption and comments in the box at (`x`, `y`). The diagram shrinks
+/// to leave room for the comments, down to about half the box; comments that still do not
+/// fit are cut off.
+#[allow(clippy::too_many_arguments)]
+fn slot(svg: &mut String, game: &Game, figure: &Figure, options: &KifuOptions, x: f64, y: f64, width: f64, height: f64) {
+    let caption_line = CAPTION_SIZE * 1.6;
+    let comment_line = COMMENT_SIZE * 1.3;
+    let mut lines: Vec<String> = figure.comments.iter().flat_map(|c| wrap(c, COMMENT_SIZE, width)).collect();
+    let text_height = caption_line + lines.len() as f64 * comment_line;
+    let max_height = (height - text_height).max(height * 0.55);
+
+    let diagram_options = DiagramOptions { markup: false, ..options.diagram.clone() };
+    let numbering = Numbering::Kifu { moves: figure.moves, restart: figure.restart };
+    let diagram_height = game.diagram(&figure.path, &diagram_options, numbering, |diagram| {
+        let layout = Layout::new(diagram, &diagram_options);
+        let scale = (width / layout.width).min(max_height / layout.height);
+        let left = x + (width - layout.width * scale) / 2.0;
+        svg.push_str(&diagram.to_svg_placed(&diagram_options, left, y, scale));
+        layout.height * scale
+    });
+
+    let mut baseline = y + diagram_height + caption_line * 0.8;
+    let _ = write!(svg, r#"<text x="{:.1}" y="{:.1}" font-size="{}" font-weight="bold" text-anchor="middle">{}</text>"#, x + width / 2.0, baseline, CAPTION_SIZE, escape(&figure.caption));
+    let fit = ((y + height - baseline) / comment_line).floor().max(0.0) as usize;
+    if lines.len() > fit {
+        lines.truncate(fit);
+        if let Some(last) = lines.last_mut() {
+            last.pop();
+            last.push('\u{2026}');
+        }
+    }
+    for line in lines {
+        baseline += comment_line;
+        let _ = write!(svg, r#"<text x="{:.1}" y="{:.1}" font-size="{}">{}</text>"#, x, baseline, COMMENT_SIZE, escape(&line));
+    }
+}
+
+/// Breaks `text` into lines about `width` points wide, at spaces where there are any.
+/// Widths are estimated, with CJK characters one em wide and others a little over half.
+fn wrap(text: &str, size: f64, width: f64) -> Vec<String> {
+    let advance = |c: char| size * if c as u32 >= 0x1100 { 1.0 } else { 0.55 };
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut used = 0.0;
+        for c in paragraph.chars() {
+            if used + advance(c) > width && !line.is_empty() && c != ' ' {
+                let rest = match line.rfind(' ') {
+                    Some(i) if i > 0 => line.split_off(i + 1),
+                    _ => String::new(),
+                };
+                lines.push(line.trim_end().to_string());
+                used = rest.chars().map(advance).sum();
+                line = rest;
+            }
+            if line.is_empty() && c == ' ' {
+                continue;
+            }
+            line.push(c);
+            used += advance(c);
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+/// Converts the page SVGs into one PDF document.
+fn write_pdf(pages: &[String], page_size: PageSize, title: &str) -> Result<Vec<u8>, SgfError> {
+    let (width, height) = page_size.points();
+    let (width, height) = (width as f32, height as f32);
+    let catalog = Ref::new(1);
+    let page_tree = Ref::new(2);
+    let info = Ref::new(3);
+    let mut next = Ref::new(4);
+    let mut pdf = Pdf::new();
+    let mut kids = Vec::new();
+    for svg in pages {
+        let tree = svg_tree(svg)?;
+        let (page, contents, graphic) = (next, Ref::new(next.get() + 1), Ref::new(next.get() + 2));
+        next = svg2pdf::convert_tree_into(&tree, svg2pdf::Options::default(), &mut pdf, graphic);
+
+        let mut page_writer = pdf.page(page);
+        page_writer.media_box(Rect::new(0.0, 0.0, width, height)).parent(page_tree).contents(contents);
+        page_writer.resources().x_objects().pair(Name(b"P"), graphic);
+        page_writer.finish();
+        let mut content = Content::new();
+        content.transform([width, 0.0, 0.0, height, 0.0, 0.0]).x_object(Name(b"P"));
+        pdf.stream(contents, &content.finish());
+        kids.push(page);
+    }
+    pdf.catalog(catalog).pages(page_tree);
+    pdf.pages(page_tree).count(kids.len() as i32).kids(kids);
+    pdf.document_info(info).title(TextStr(title));
+    Ok(pdf.finish())
+}
@@ -0,0 +1,587 @@
+use sgf_parse::go::{Move as GoMove, Point as GoPoint, Prop};
+use sgf_parse::{Color, Double};
+
+use crate::{
+    board_size_value, is_sgf_pass, parse_board_size, set_property, sgf_coord, sgf_to_point, Game, Move,
+    Point, SgfNode, SgfProperty, StoneColor,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum MarkupShape {
+    /// CR
+    Circle,
+    /// MA
+    Cross,
+    /// SQ
+    Square,
+    /// TR
+    Triangle,
+    /// SL
+    Selected,
+    /// DD; an empty list clears dimming inherited from earlier nodes.
+    Dimmed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum Annotation {
+    /// GB
+    GoodForBlack,
+    /// GW
+    GoodForWhite,
+    /// DM
+    Even,
+    /// UC
+    Unclear,
+    /// HO
+    Hotspot,
+    /// TE
+    Tesuji,
+    /// BM
+    BadMove,
+    /// DO
+    Doubtful,
+    /// IT
+    Interesting,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum GameInfoField {
+    BlackName,
+    BlackRank,
+    BlackTeam,
+    WhiteName,
+    WhiteRank,
+    WhiteTeam,
+    Result,
+    Date,
+    Event,
+    Round,
+    Place,
+    GameName,
+    GameComment,
+    Opening,
+    Overtime,
+    Rules,
+    Source,
+    User,
+    Annotator,
+    Copyright,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum RootField {
+    FileFormat,
+    GameType,
+    Charset,
+    Application,
+    Style,
+}
+
+#[derive(uniffi::Record, Clone, Debug, PartialEq)]
+pub struct Label {
+    pub point: Point,
+    pub text: String,
+}
+
+/// Start and end of an arrow or line.
+#[derive(uniffi::Record, Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub from: Point,
+    pub to: Point,
+}
+
+/// An SGF property with its values decoded. Anything that is not recognised or does not
+/// decode on the board it is read for comes back as `Unknown`, exactly as stored.
+#[derive(uniffi::Enum, Clone, Debug, PartialEq)]
+pub enum Property {
+    // Moves
+    Move { color: StoneColor, mv: Move },
+    /// KO: play the move even if it is illegal.
+    ForceMove,
+    MoveNumber { number: i64 },
+    // Setup; AE has no color
+    Setup { color: Option<StoneColor>, points: Vec<Point> },
+    PlayerToMove { color: StoneColor },
+    // Markup
+    Markup { shape: MarkupShape, points: Vec<Point> },
+    Labels { labels: Vec<Label> },
+    Arrows { segments: Vec<Segment> },
+    Lines { segments: Vec<Segment> },
+    /// TB/TW
+    Territory { color: StoneColor, points: Vec<Point> },
+    // Annotations; `emphasized` is the SGF double "2" and is always false for DO and IT
+    Annotation { annotation: Annotation, emphasized: bool },
+    /// V: estimated score, positive when Black is ahead.
+    Value { value: f64 },
+    Comment { text: String },
+    NodeName { text: String },
+    // Timing
+    TimeLeft { color: StoneColor, seconds: f64 },
+    /// OB/OW: moves left in the current byo-yomi period.
+    MovesLeft { color: StoneColor, count: i64 },
+    // Root and game info
+    Root { field: RootField, value: String },
+    BoardSize { width: u32, height: u32 },
+    GameInfo { field: GameInfoField, text: String },
+    Komi { komi: f64 },
+    Handicap { stones: i64 },
+    TimeLimit { seconds: f64 },
+    Unknown { identifier: String, values: Vec<String> },
+}
+
+const MARKUP: [(&str, MarkupShape); 6] = [
+    ("CR", MarkupShape::Circle),
+    ("MA", MarkupShape::Cross),
+    ("SQ", MarkupShape::Square),
+    ("TR", MarkupShape::Triangle),
+    ("SL", MarkupShape::Selected),
+    ("DD", MarkupShape::Dimmed),
+];
+
+const ANNOTATIONS: [(&str, Annotation); 9] = [
+    ("GB", Annotation::GoodForBlack),
+    ("GW", Annotation::GoodForWhite),
+    ("DM", Annotation::Even),
+    ("UC", Annotation::Unclear),
+    ("HO", Annotation::Hotspot),
+    ("TE", Annotation::Tesuji),
+    ("BM", Annotation::BadMove),
+    ("DO", Annotation::Doubtful),
+    ("IT", Annotation::Interesting),
+];
+
+const GAME_INFO: [(&str, GameInfoField); 20] = [
+    ("PB", GameInfoField::BlackName),
+    ("BR", GameInfoField::BlackRank),
+    ("BT", GameInfoField::BlackTeam),
+    ("PW", GameInfoField::WhiteName),
+    ("WR", GameInfoField::WhiteRank),
+    ("WT", GameInfoField::WhiteTeam),
+    ("RE", GameInfoField::Result),
+    ("DT", GameInfoField::Date),
+    ("EV", GameInfoField::Event),
+    ("RO", GameInfoField::Round),
+    ("PC", GameInfoField::Place),
+    ("GN", GameInfoField::GameName),
+    ("GC", GameInfoField::GameComment),
+    ("ON", GameInfoField::Opening),
+    ("OT", GameInfoField::Overtime),
+    ("RU", GameInfoField::Rules),
+    ("SO", GameInfoField::Source),
+    ("US", GameInfoField::User),
+    ("AN", GameInfoField::Annotator),
+    ("CP", GameInfoField::Copyright),
+];
+
+const ROOT: [(&str, RootField); 5] = [
+    ("FF", RootField::FileFormat),
+    ("GM", RootField::GameType),
+    ("CA", RootField::Charset),
+    ("AP", RootField::Application),
+    ("ST", RootField::Style),
+];
+
+/// Properties whose values are "first:text", where the text may itself contain colons.
+const COMPOSED_TEXT: [&str; 3] = ["LB", "AP", "FG"];
+
+/// Identifiers whose values hold points, so a value that does not decode is a bad coordinate.
+pub(crate) const POINT_PROPERTIES: [&str; 16] =
+    ["B", "W", "AB", "AW", "AE", "CR", "MA", "SQ", "TR", "SL", "DD", "LB", "AR", "LN", "TB", "TW"];
+
+/// Whether `typed_property` decodes `identifier`; for these, `Unknown` means a malformed value.
+pub(crate) fn is_typed_identifier(identifier: &str) -> bool {
+    const OTHERS: [&str; 24] = [
+        "B", "W", "KO", "MN", "AB", "AW", "AE", "PL", "LB", "AR", "LN", "TB", "TW", "V", "C", "N", "BL", "WL", "OB",
+        "OW", "SZ", "KM", "HA", "TM",
+    ];
+    MARKUP.iter().map(|(id, _)| *id)
+        .chain(ANNOTATIONS.iter().map(|(id, _)| *id))
+        .chain(GAME_INFO.iter().map(|(id, _)| *id))
+        .chain(ROOT.iter().map(|(id, _)| *id))
+        .chain(OTHERS)
+        .any(|id| id == identifier)
+}
+
+#[uniffi::export]
+impl SgfNode {
+    /// The node's properties decoded for a board of the given size, in stored order.
+    pub fn get_typed_properties(&self, width: u32, height: u32) -> Vec<Property> {
+        self.properties
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|p| typed_property(p.clone(), width, height))
+            .collect()
+    }
+
+    /// Stores `property`, replacing any existing property with the same identifier.
+    pub fn set_typed_property(&self, property: Property) {
+        let raw = raw_property(property);
+        set_property(&mut self.properties.lock().unwrap(), &raw.identifier, raw.values);
+    }
+}
+
+#[uniffi::export]
+impl Game {
+    /// Properties of the current node, decoded for this game's board size.
+    pub fn get_typed_properties(&self) -> Vec<Property> {
+        let state = self.state.lock().unwrap();
+        state.current_node.get_typed_properties(state.width, state.height)
+    }
+}
+
+/// Decodes a stored property; values that do not fit the board or the SGF type stay `Unknown`.
+#[uniffi::export]
+pub fn typed_property(property: SgfProperty, width: u32, height: u32) -> Property {
+    decode(&property, width, height).unwrap_or(Property::Unknown {
+        identifier: property.identifier,
+        values: property.values,
+    })
+}
+
+/// Encodes a typed property into the identifier and unescaped values `SgfNode` stores.
+#[uniffi::export]
+pub fn raw_property(property: Property) -> SgfProperty {
+    let raw = |id: &str, values: Vec<String>| SgfProperty { identifier: id.to_string(), values };
+    match property {
+        Property::Move { color, mv } => {
+            let value = match mv {
+                Move::Pass => String::new(),
+                Move::Play { point } => sgf_coord(point.x, point.y),
+            };
+            raw(color_id(color, "B", "W"), vec![value])
+        }
+        Property::ForceMove => raw("KO", vec![String::new()]),
+        Property::MoveNumber { number } => raw("MN", vec![number.to_string()]),
+        Property::Setup { color, points } => {
+            let id = color.map_or("AE", |c| color_id(c, "AB", "AW"));
+            raw(id, point_values(&points))
+        }
+        Property::PlayerToMove { color } => raw("PL", vec![color_id(color, "B", "W").to_string()]),
+        Property::Markup { shape, points } => raw(id_of(&MARKUP, shape), point_values(&points)),
+        Property::Labels { labels } => raw(
+            "LB",
+            labels
+                .iter()
+                .map(|l| format!("{}:{}", sgf_coord(l.point.x, l.point.y), l.text))
+                .collect(),
+        ),
+        Property::Arrows { segments } => raw("AR", segment_values(&segments)),
+        Property::Lines { segments } => raw("LN", segment_values(&segments)),
+        Property::Territory { color, points } => raw(color_id(color, "TB", "TW"), point_values(&points)),
+        Property::Annotation { annotation, emphasized } => {
+            let value = match annotation {
+                Annotation::Doubtful | Annotation::Interesting => "",
+                _ if emphasized => "2",
+                _ => "1",
+            };
+            raw(id_of(&ANNOTATIONS, annotation), vec![value.to_string()])
+        }
+        Property::Value { value } => raw("V", vec![value.to_string()]),
+        Property::Comment { text } => raw("C", vec![text]),
+        Property::NodeName { text } => raw("N", vec![text]),
+        Property::TimeLeft { color, seconds } => raw(color_id(color, "BL", "WL"), vec![seconds.to_string()]),
+        Property::MovesLeft { color, count } => raw(color_id(color, "OB", "OW"), vec![count.to_string()]),
+        Property::Root { field, value } => raw(id_of(&ROOT, field), vec![value]),
+        Property::BoardSize { width, height } => raw("SZ", vec![board_size_value(width, height)]),
+        Property::GameInfo { field, text } => raw(id_of(&GAME_INFO, field), vec![text]),
+        Property::Komi { komi } => raw("KM", vec![komi.to_string()]),
+        Property::Handicap { stones } => raw("HA", vec![stones.to_string()]),
+        Property::TimeLimit { seconds } => raw("TM", vec![seconds.to_string()]),
+        Property::Unknown { identifier, values } => SgfProperty { identifier, values },
+    }
+}
+
+/// Identifier of `wanted` in one of the lookup tables above.
+fn id_of<T: PartialEq>(table: &[(&'static str, T)], wanted: T) -> &'static str {
+    table.iter().find(|(_, v)| *v == wanted).map(|(id, _)| *id).unwrap()
+}
+
+fn color_id(color: StoneColor, black: &'static str, white: &'static str) -> &'static str {
+    match color {
+        StoneColor::Black => black,
+        StoneColor::White => white,
+    }
+}
+
+fn point_values(points: &[Point]) -> Vec<String> {
+    if points.is_empty() {
+        // An empty list is still written as one empty value (DD[], VW[])
+        return vec![String::new()];
+    }
+    points.iter().map(|p| sgf_coord(p.x, p.y)).collect()
+}
+
+fn segment_values(segments: &[Segment]) -> Vec<String> {
+    segments
+        .iter()
+        .map(|s| format!("{}:{}", sgf_coord(s.from.x, s.from.y), sgf_coord(s.to.x, s.to.y)))
+        .collect()
+}
+
+fn decode(property: &SgfProperty, width: u32, height: u32) -> Option<Property> {
+    let id = property.identifier.as_str();
+    let values = &property.values;
+    let single = || match values.as_slice() {
+        [value] => Some(value.clone()),
+        _ => None,
+    };
+    let point = |value: &str| {
+        let (x, y) = sgf_to_point(value)?;
+        (x < width && y < height).then_some(Point { x, y })
+    };
+    let points = |allow_empty: bool| -> Option<Vec<Point>> {
+        if let [value] = values.as_slice() {
+            if value.is_empty() {
+                return allow_empty.then(Vec::new);
+            }
+        }
+        let mut points = Vec::new();
+        for value in values {
+            match value.split_once(':') {
+                // Compressed rectangle "aa:cc"
+                Some((from, to)) => {
+                    let (from, to) = (point(from)?, point(to)?);
+                    for y in from.y.min(to.y)..=from.y.max(to.y) {
+                        for x in from.x.min(to.x)..=from.x.max(to.x) {
+                            points.push(Point { x, y });
+                        }
+                    }
+                }
+                None => points.push(point(value)?),
+            }
+        }
+        Some(points)
+    };
+    let segments = || -> Option<Vec<Segment>> {
+        values
+            .iter()
+            .map(|v| {
+                let (from, to) = v.split_once(':')?;
+                Some(Segment { from: point(from)?, to: point(to)? })
+            })
+            .collect()
+    };
+    let real = || single()?.trim().parse::<f64>().ok().filter(|v| v.is_finite());
+    let number = || single()?.trim().parse::<i64>().ok();
+    let color = |black: &str| if id == black { StoneColor::Black } else { StoneColor::White };
+
+    if let Some((_, shape)) = MARKUP.iter().find(|(k, _)| *k == id) {
+        return Some(Property::Markup { shape: *shape, points: points(*shape == MarkupShape::Dimmed)? });
+    }
+    if let Some((_, annotation)) = ANNOTATIONS.iter().find(|(k, _)| *k == id) {
+        let emphasized = match (annotation, single()?.as_str()) {
+            (Annotation::Doubtful | Annotation::Interesting, "") => false,
+            (Annotation::Doubtful | Annotation::Interesting, _) => return None,
+            (_, "1") => false,
+            (_, "2") => true,
+            _ => return None,
+        };
+        return Some(Property::Annotation { annotation: *annotation, emphasized });
+    }
+    if let Some((_, field)) = GAME_INFO.iter().find(|(k, _)| *k == id) {
+        return Some(Property::GameInfo { field: *field, text: single()? });
+    }
+    if let Some((_, field)) = ROOT.iter().find(|(k, _)| *k == id) {
+        return Some(Property::Root { field: *field, value: single()? });
+    }
+
+    Some(match id {
+        "B" | "W" => {
+            let value = single()?;
+            let mv = if is_sgf_pass(&value, width, height) {
+                Move::Pass
+            } else {
+                Move::Play { point: point(&value)? }
+            };
+            Property::Move { color: color("B"), mv }
+        }
+        "KO" => (single()?.is_empty()).then_some(Property::ForceMove)?,
+        "MN" => Property::MoveNumber { number: number()? },
+        "AB" | "AW" => Property::Setup { color: Some(color("AB")), points: points(false)? },
+        "AE" => Property::Setup { color: None, points: points(false)? },
+        "PL" => match single()?.as_str() {
+            "B" => Property::PlayerToMove { color: StoneColor::Black },
+            "W" => Property::PlayerToMove { color: StoneColor::White },
+            _ => return None,
+        },
+        "LB" => Property::Labels {
+            labels: values
+                .iter()
+                .map(|v| {
+                    let (p, text) = v.split_once(':')?;
+                    Some(Label { point: point(p)?, text: text.to_string() })
+                })
+                .collect::<Option<_>>()?,
+        },
+        "AR" => Property::Arrows { segments: segments()? },
+        "LN" => Property::Lines { segments: segments()? },
+        "TB" | "TW" => Property::Territory { color: color("TB"), points: points(true)? },
+        "V" => Property::Value { value: real()? },
+        "C" => Property::Comment { text: single()? },
+        "N" => Property::NodeName { text: single()? },
+        "BL" | "WL" => Property::TimeLeft { color: color("BL"), seconds: real()? },
+        "OB" | "OW" => Property::MovesLeft { color: color("OB"), count: number()? },
+        "SZ" => {
+            let (width, height) = parse_board_size(&single()?)?;
+            Property::BoardSize { width, height }
+        }
+        "KM" => Property::Komi { komi: real()? },
+        "HA" => Property::Handicap { stones: number()? },
+        "TM" => Property::TimeLimit { seconds: real()? },
+        _ => return None,
+    })
+}
+
+/// Values of a parsed property as `SgfNode` stores them: unescaped, with points written
+/// for boards up to 52x52 and lists in board order so saving is deterministic.
+pub(crate) fn raw_values(prop: &Prop) -> Vec<String> {
+    let point = |p: &GoPoint| sgf_coord(p.x as u32, p.y as u32);
+    let points = |set: &std::collections::HashSet<GoPoint>| {
+        let mut sorted: Vec<&GoPoint> = set.iter().collect();
+        sorted.sort_by_key(|p| (p.y, p.x));
+        let values: Vec<String> = sorted.into_iter().map(point).collect();
+        if values.is_empty() {
+            vec![String::new()]
+        } else {
+            values
+        }
+    };
+    let pairs = |set: &std::collections::HashSet<(GoPoint, GoPoint)>| {
+        let mut sorted: Vec<_> = set.iter().collect();
+        sorted.sort_by_key(|(a, b)| (a.y, a.x, b.y, b.x));
+        sorted.into_iter().map(|(a, b)| format!("{}:{}", point(a), point(b))).collect()
+    };
+    let double = |d: &Double| match d {
+        Double::One => "1".to_string(),
+        Double::Two => "2".to_string(),
+    };
+    let color = |c: &Color| match c {
+        Color::Black => "B".to_string(),
+        Color::White => "W".to_string(),
+    };
+
+    let value = match prop {
+        Prop::B(mv) | Prop::W(mv) => match mv {
+            GoMove::Pass => String::new(),
+            GoMove::Move(p) => point(p),
+        },
+        Prop::AB(set) | Prop::AW(set) | Prop::AE(set) | Prop::CR(set) | Prop::DD(set) | Prop::MA(set)
+        | Prop::SL(set) | Prop::SQ(set) | Prop::TR(set) | Prop::VW(set) | Prop::TB(set) | Prop::TW(set) => {
+            return points(set)
+        }
+        Prop::AR(set) | Prop::LN(set) => return pairs(set),
+        Prop::LB(set) => {
+            let mut sorted: Vec<_> = set.iter().collect();
+            sorted.sort_by(|(a, s), (b, t)| (a.y, a.x, &s.text).cmp(&(b.y, b.x, &t.text)));
+            return sorted.into_iter().map(|(p, text)| format!("{}:{}", point(p), text.text)).collect();
+        }
+        Prop::KO | Prop::DO | Prop::IT => String::new(),
+        Prop::MN(n) | Prop::FF(n) | Prop::GM(n) | Prop::ST(n) | Prop::OB(n) | Prop::OW(n) | Prop::PM(n)
+        | Prop::HA(n) => n.to_string(),
+        Prop::V(r) | Prop::TM(r) | Prop::BL(r) | Prop::WL(r) | Prop::KM(r) => r.to_string(),
+        Prop::DM(d) | Prop::GB(d) | Prop::GW(d) | Prop::HO(d) | Prop::UC(d) | Prop::BM(d) | Prop::TE(d) => {
+            double(d)
+        }
+        Prop::PL(c) => color(c),
+        Prop::C(t) | Prop::GC(t) => t.text.clone(),
+        Prop::N(t) | Prop::CA(t) | Prop::AN(t) | Prop::BR(t) | Prop::BT(t) | Prop::CP(t) | Prop::DT(t)
+        | Prop::EV(t) | Prop::GN(t) | Prop::ON(t) | Prop::OT(t) | Prop::PB(t) | Prop::PC(t) | Prop::PW(t)
+        | Prop::RE(t) | Prop::RO(t) | Prop::RU(t) | Prop::SO(t) | Prop::US(t) | Prop::WR(t) | Prop::WT(t) => {
+            t.text.clone()
+        }
+        Prop::AP((name, version)) => format!("{}:{}", name.text, version.text),
+        Prop::SZ((w, h)) => board_size_value(*w as u32, *h as u32),
+        Prop::FG(fg) => match fg {
+            Some((flags, text)) => format!("{}:{}", flags, text.text),
+            None => String::new(),
+        },
+        Prop::Unknown(_, values) | Prop::Invalid(_, values) => return values.clone(),
+    };
+    vec![value]
+}
+
+/// Escapes a stored value for writing between brackets. In composed text values every
+/// colon after the separator is escaped as well.
+pub(crate) fn escape_value(identifier: &str, value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\").replace(']', "\\]");
+    if !COMPOSED_TEXT.contains(&identifier) {
+        return escaped;
+    }
+    match escaped.split_once(':') {
+        Some((first, text)) => format!("{}:{}", first, text.replace(':', "\\:")),
+        None => escaped,
+    }
+}
+
+/// Identifiers of one node with their values as written, escapes included.
+pub(crate) type SourceNode = Vec<(String, Vec<String>)>;
+
+/// Every node of SGF text in file order, which is the order of a depth-first walk of its
+/// game trees. Lower-case letters in identifiers are dropped, as the parser does for FF[3].
+pub(crate) fn source_nodes(sgf: &str) -> Vec<SourceNode> {
+    let mut nodes: Vec<SourceNode> = Vec::new();
+    let mut identifier = String::new();
+    let mut chars = sgf.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => nodes.push(Vec::new()),
+            '[' => {
+                let mut value = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            value.push(c);
+                            value.extend(chars.next());
+                        }
+                        ']' => break,
+                        _ => value.push(c),
+                    }
+                }
+                // Further values of a list follow without an identifier
+                let Some(node) = nodes.last_mut() else { continue };
+                if !identifier.is_empty() {
+                    node.push((std::mem::take(&mut identifier), vec![]));
+                }
+                if let Some((_, values)) = node.last_mut() {
+                    values.push(value);
+                }
+            }
+            c if c.is_ascii_uppercase() => identifier.push(c),
+            _ => {}
+        }
+    }
+    nodes
+}
+
+/// Removes SGF soft line breaks (a backslash directly before a line break inside a value).
+/// The parser unescapes the line break itself and would keep it as a hard break.
+pub(crate) fn remove_soft_line_breaks(sgf: &str) -> String {
+    let mut out = String::with_capacity(sgf.len());
+    let mut chars = sgf.chars().peekable();
+    let mut in_value = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '[' if !in_value => in_value = true,
+            ']' if in_value => in_value = false,
+            '\\' if in_value => match chars.next() {
+                // "\r\n" and "\n\r" count as one line break
+                Some(first @ ('\n' | '\r')) => {
+                    if chars.peek().is_some_and(|&next| matches!(next, '\n' | '\r') && next != first) {
+                        chars.next();
+                    }
+                    continue;
+                }
+                Some(escaped) => {
+                    out.push(c);
+                    out.push(escaped);
+                    continue;
+                }
+                None => {}
+            },
+            _ => {}
+        }
+        out.push(c);
+    }
+    out
+}
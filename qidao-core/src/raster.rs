@@ -0,0 +1,160 @@
+use std::sync::OnceLock;
+
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg::{self, fontdb, TreeParsing, TreePostProc};
+
+use crate::diagram::{Diagram, Numbering};
+use crate::{Board, DiagramOptions, Game, MoveRange, SgfError};
+
+/// Largest image side `to_png` and `to_gif` produce.
+const MAX_IMAGE_SIZE: u32 = 4096;
+
+#[derive(uniffi::Record, Clone, Debug, PartialEq)]
+pub struct AnimationOptions {
+    /// Board drawing; `move_range` and `mark_last_move` apply to every frame.
+    pub diagram: DiagramOptions,
+    /// Image width in pixels; the height follows from the diagram.
+    pub width: u32,
+    pub frame_delay_ms: u32,
+    /// How long the last frame stays before the animation loops.
+    pub final_delay_ms: u32,
+    /// First frame: the position after this move; 0 starts from the root.
+    pub from_move: u32,
+    /// Numbers the stones still on the board, counting from `from_move + 1`.
+    pub move_numbers: bool,
+    pub repeat: bool,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        AnimationOptions {
+            diagram: DiagramOptions { coordinates: false, ..DiagramOptions::default() },
+            width: 480,
+            frame_delay_ms: 800,
+            final_delay_ms: 3000,
+            from_move: 0,
+            move_numbers: false,
+            repeat: true,
+        }
+    }
+}
+
+#[uniffi::export]
+pub fn default_animation_options() -> AnimationOptions {
+    AnimationOptions::default()
+}
+
+#[uniffi::export]
+impl Board {
+    /// Draws the stones as a PNG `width` pixels wide; see `to_svg`.
+    pub fn to_png(&self, options: DiagramOptions, width: u32) -> Result<Vec<u8>, SgfError> {
+        encode_png(&rasterize(&Diagram::empty(self).to_svg(&options), width)?)
+    }
+}
+
+#[uniffi::export]
+impl Game {
+    /// Draws what `to_svg` draws as a PNG `width` pixels wide.
+    pub fn to_png(&self, options: DiagramOptions, width: u32) -> Result<Vec<u8>, SgfError> {
+        let path = self.state.lock().unwrap().path();
+        let svg = self.diagram(&path, &options, Numbering::Figure, |diagram| diagram.to_svg(&options));
+        encode_png(&rasterize(&svg, width)?)
+    }
+
+    /// Replays the current variation as an animated GIF: the path to the current node, then
+    /// on through the first children to the end. One frame per node, starting after
+    /// `options.from_move`. The current node is unchanged afterwards.
+    pub fn to_gif(&self, options: AnimationOptions) -> Result<Vec<u8>, SgfError> {
+        let mut line = self.state.lock().unwrap().path();
+        while let Some(next) = line.last().unwrap().get_children().first().cloned() {
+            line.push(next);
+        }
+        let from = (options.from_move as usize).min(line.len() - 1);
+
+        let mut diagram = options.diagram.clone();
+        if options.move_numbers {
+            diagram.move_range = Some(MoveRange { first: options.from_move + 1, last: u32::MAX });
+        }
+        let frames: Result<Vec<Pixmap>, SgfError> = (from..line.len())
+            .map(|end| {
+                let svg = self.diagram(&line[..=end], &diagram, Numbering::OnBoard, |d| d.to_svg(&diagram));
+                rasterize(&svg, options.width)
+            })
+            .collect();
+        encode_gif(&frames?, &options)
+    }
+}
+
+/// Renders SVG text into a pixmap `width` pixels wide.
+fn rasterize(svg: &str, width: u32) -> Result<Pixmap, SgfError> {
+    let error = |message: String| SgfError::ParseError { message };
+    let tree = svg_tree(svg)?;
+    let scale = width as f32 / tree.size.width();
+    let height = (tree.size.height() * scale).round() as u32;
+    if !(1..=MAX_IMAGE_SIZE).contains(&width) || !(1..=MAX_IMAGE_SIZE).contains(&height) {
+        return Err(error(format!("Image size {}x{} is out of range", width, height)));
+    }
+    let mut pixmap = Pixmap::new(width, height).ok_or_else(|| error("Cannot allocate image".into()))?;
+    resvg::render(&tree, Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+    Ok(pixmap)
+}
+
+/// Parses SVG text with its text already turned into outlines.
+pub(crate) fn svg_tree(svg: &str) -> Result<usvg::Tree, SgfError> {
+    let mut tree = usvg::Tree::from_str(svg, &usvg::Options::default())
+        .map_err(|e| SgfError::ParseError { message: e.to_string() })?;
+    tree.postprocess(usvg::PostProcessingSteps { convert_text_into_paths: true }, font_database());
+    Ok(tree)
+}
+
+/// System fonts, loaded once. Generic families point at installed fonts, so text renders
+/// on machines without the fonts the themes name, as long as there is any font at all.
+fn font_database() -> &'static fontdb::Database {
+    static FONTS: OnceLock<fontdb::Database> = OnceLock::new();
+    FONTS.get_or_init(|| {
+        let mut db = fontdb::Database::new();
+        db.load_system_fonts();
+        let families: Vec<String> =
+            db.faces().flat_map(|face| face.families.iter().map(|(name, _)| name.clone())).collect();
+        let pick = |preferred: &[&str], hint: &str| {
+            preferred
+                .iter()
+                .map(|name| name.to_string())
+                .find(|name| families.contains(name))
+                .or_else(|| families.iter().find(|name| name.contains(hint) && !name.contains("Mono")).cloned())
+                .or_else(|| families.first().cloned())
+        };
+        if let Some(family) = pick(&["Helvetica", "Arial", "DejaVu Sans", "Liberation Sans"], "Sans") {
+            db.set_sans_serif_family(family);
+        }
+        if let Some(family) = pick(&["Times New Roman", "DejaVu Serif", "Liberation Serif"], "Serif") {
+            db.set_serif_family(family);
+        }
+        db
+    })
+}
+
+fn encode_png(pixmap: &Pixmap) -> Result<Vec<u8>, SgfError> {
+    pixmap.encode_png().map_err(|e| SgfError::ParseError { message: e.to_string() })
+}
+
+fn encode_gif(frames: &[Pixmap], options: &AnimationOptions) -> Result<Vec<u8>, SgfError> {
+    let error = |e: gif::EncodingError| SgfError::ParseError { message: e.to_string() };
+    let (width, height) = frames.first().map_or((1, 1), |f| (f.width() as u16, f.height() as u16));
+    let mut out = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut out, width, height, &[]).map_err(error)?;
+        if options.repeat {
+            encoder.set_repeat(gif::Repeat::Infinite).map_err(error)?;
+        }
+        for (i, pixmap) in frames.iter().enumerate() {
+            // Diagrams are opaque, so the premultiplied pixels are plain RGBA
+            let mut rgba = pixmap.data().to_vec();
+            let mut frame = gif::Frame::from_rgba_speed(width, height, &mut rgba, 10);
+            let delay = if i + 1 == frames.len() { options.final_delay_ms } else { options.frame_delay_ms };
+            frame.delay = (delay / 10).min(u16::MAX as u32) as u16;
+            encoder.write_frame(&frame).map_err(error)?;
+        }
+    }
+    Ok(out)
+}
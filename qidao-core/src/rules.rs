@@ -0,0 +1,295 @@
+use crate::SuperkoPolicy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum ScoringType {
+    /// Stones plus surrounded empty points (Chinese, AGA, New Zealand, Tromp-Taylor).
+    Area,
+    /// Surrounded empty points plus prisoners (Japanese, Korean).
+    Territory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum TaxRule {
+    /// Every surrounded empty point counts.
+    None,
+    /// Empty points inside seki are not counted.
+    Seki,
+    /// Each group is taxed two points for its two eyes (stone scoring).
+    All,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum HandicapBonus {
+    /// White receives no compensation for handicap stones.
+    Zero,
+    /// White receives one point per handicap stone after the first.
+    NMinusOne,
+    /// White receives one point per handicap stone.
+    N,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Record)]
+pub struct Rules {
+    pub ko_rule: SuperkoPolicy,
+    /// Whether a move that leaves its own multi-stone group without liberties is legal.
+    pub suicide_allowed: bool,
+    pub scoring: ScoringType,
+    pub tax: TaxRule,
+    pub handicap_bonus: HandicapBonus,
+    /// The rule set these rules were chosen as. Japanese and Korean rules play alike and
+    /// differ only here, so it decides what `RU` and KataGo are told.
+    pub name: Option<RuleSet>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum RuleSet {
+    Chinese,
+    Japanese,
+    Korean,
+    Aga,
+    NewZealand,
+    TrompTaylor,
+}
+
+impl RuleSet {
+    pub const ALL: [RuleSet; 6] = [
+        RuleSet::Chinese,
+        RuleSet::Japanese,
+        RuleSet::Korean,
+        RuleSet::Aga,
+        RuleSet::NewZealand,
+        RuleSet::TrompTaylor,
+    ];
+
+    pub fn rules(self) -> Rules {
+        match self {
+            RuleSet::Chinese => Rules {
+                ko_rule: SuperkoPolicy::Positional,
+                suicide_allowed: false,
+                scoring: ScoringType::Area,
+                tax: TaxRule::None,
+                handicap_bonus: HandicapBonus::N,
+                name: Some(self),
+            },
+            RuleSet::Japanese | RuleSet::Korean => Rules {
+                ko_rule: SuperkoPolicy::None,
+                suicide_allowed: false,
+                scoring: ScoringType::Territory,
+                tax: TaxRule::Seki,
+                handicap_bonus: HandicapBonus::Zero,
+                name: Some(self),
+            },
+            RuleSet::Aga => Rules {
+                ko_rule: SuperkoPolicy::Situational,
+                suicide_allowed: false,
+                scoring: ScoringType::Area,
+                tax: TaxRule::None,
+                handicap_bonus: HandicapBonus::NMinusOne,
+                name: Some(self),
+            },
+            RuleSet::NewZealand => Rules {
+                ko_rule: SuperkoPolicy::Situational,
+                suicide_allowed: true,
+                scoring: ScoringType::Area,
+                tax: TaxRule::None,
+                handicap_bonus: HandicapBonus::Zero,
+                name: Some(self),
+            },
+            RuleSet::TrompTaylor => Rules {
+                ko_rule: SuperkoPolicy::Positional,
+                suicide_allowed: true,
+                scoring: ScoringType::Area,
+                tax: TaxRule::None,
+                handicap_bonus: HandicapBonus::Zero,
+                name: Some(self),
+            },
+        }
+    }
+
+    /// Value written to the SGF `RU` property.
+    pub fn sgf_name(self) -> &'static str {
+        match self {
+            RuleSet::Chinese => "Chinese",
+            RuleSet::Japanese => "Japanese",
+            RuleSet::Korean => "Korean",
+            RuleSet::Aga => "AGA",
+            RuleSet::NewZealand => "NZ",
+            RuleSet::TrompTaylor => "Tromp-Taylor",
+        }
+    }
+
+    /// Name understood by KataGo's `rules` query field. KataGo plays its `chinese` rules
+    /// with simple ko, so `Rules::to_katago` spells out ours instead of using the name.
+    pub fn katago_name(self) -> &'static str {
+        match self {
+            RuleSet::Chinese => "chinese",
+            RuleSet::Japanese => "japanese",
+            RuleSet::Korean => "korean",
+            RuleSet::Aga => "aga",
+            RuleSet::NewZealand => "new-zealand",
+            RuleSet::TrompTaylor => "tromp-taylor",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<RuleSet> {
+        let key: String = name
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        match key.as_str() {
+            "chinese" | "cn" | "chn" | "china" => Some(RuleSet::Chinese),
+            "japanese" | "jp" | "jpn" | "japan" => Some(RuleSet::Japanese),
+            "korean" | "kr" | "kor" | "korea" => Some(RuleSet::Korean),
+            "aga" | "american" => Some(RuleSet::Aga),
+            "nz" | "newzealand" => Some(RuleSet::NewZealand),
+            "tromptaylor" | "tt" => Some(RuleSet::TrompTaylor),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        RuleSet::Chinese.rules()
+    }
+}
+
+impl Rules {
+    /// Parses an SGF `RU` value: either a rule set name ("Chinese", "NZ", ...)
+    /// or KataGo's compact form ("koPOSITIONALscoreAREAtaxNONEsui1whbN").
+    pub fn from_sgf(value: &str) -> Option<Rules> {
+        let value = value.trim();
+        RuleSet::from_name(value)
+            .map(RuleSet::rules)
+            .or_else(|| Self::from_compact_string(value))
+    }
+
+    /// Komi once handicap stones are placed. Territory rules, and area rules that give
+    /// White `handicap_bonus` points at scoring time, keep half a point; area rules
+    /// without a bonus (New Zealand, Tromp-Taylor) play handicap games without komi.
+    pub fn handicap_komi(&self) -> f64 {
+        match (self.scoring, self.handicap_bonus) {
+            (ScoringType::Area, HandicapBonus::Zero) => 0.0,
+            _ => 0.5,
+        }
+    }
+
+    /// Value for the SGF `RU` property.
+    pub fn to_sgf(&self) -> String {
+        match self.rule_set() {
+            Some(set) => set.sgf_name().to_string(),
+            None => self.compact_string(),
+        }
+    }
+
+    /// Value for the `rules` field of a KataGo analysis query, with the same ko rule.
+    pub fn to_katago(&self) -> String {
+        match self.rule_set() {
+            Some(RuleSet::Chinese) | None => self.compact_string(),
+            Some(set) => set.katago_name().to_string(),
+        }
+    }
+
+    /// The named rule set these rules correspond to, if any: `name` while the rules still
+    /// match it. Unnamed rules that match Japanese ones report as Japanese.
+    pub fn rule_set(&self) -> Option<RuleSet> {
+        let unnamed = |rules: Rules| Rules { name: None, ..rules };
+        match self.name {
+            Some(set) => (unnamed(set.rules()) == unnamed(*self)).then_some(set),
+            None => RuleSet::ALL.into_iter().find(|set| unnamed(set.rules()) == *self),
+        }
+    }
+
+    fn compact_string(&self) -> String {
+        let ko = match self.ko_rule {
+            SuperkoPolicy::None => "SIMPLE",
+            SuperkoPolicy::Positional => "POSITIONAL",
+            SuperkoPolicy::Situational => "SITUATIONAL",
+        };
+        let score = match self.scoring {
+            ScoringType::Area => "AREA",
+            ScoringType::Territory => "TERRITORY",
+        };
+        let tax = match self.tax {
+            TaxRule::None => "NONE",
+            TaxRule::Seki => "SEKI",
+            TaxRule::All => "ALL",
+        };
+        let whb = match self.handicap_bonus {
+            HandicapBonus::Zero => "0",
+            HandicapBonus::NMinusOne => "N-1",
+            HandicapBonus::N => "N",
+        };
+        format!(
+            "ko{}score{}tax{}sui{}whb{}",
+            ko,
+            score,
+            tax,
+            if self.suicide_allowed { 1 } else { 0 },
+            whb
+        )
+    }
+
+    fn from_compact_string(value: &str) -> Option<Rules> {
+        // All fields except `whb` are required; anything else is not a rule string we understand.
+        let field = |key: &str| -> Option<&str> {
+            let start = value.find(key)? + key.len();
+            let rest = &value[start..];
+            let end = rest
+                .find(|c: char| c.is_ascii_lowercase())
+                .unwrap_or(rest.len());
+            Some(&rest[..end])
+        };
+
+        let ko_rule = match field("ko")? {
+            "SIMPLE" => SuperkoPolicy::None,
+            "POSITIONAL" => SuperkoPolicy::Positional,
+            "SITUATIONAL" => SuperkoPolicy::Situational,
+            _ => return None,
+        };
+        let scoring = match field("score")? {
+            "AREA" => ScoringType::Area,
+            "TERRITORY" => ScoringType::Territory,
+            _ => return None,
+        };
+        let tax = match field("tax")? {
+            "NONE" => TaxRule::None,
+            "SEKI" => TaxRule::Seki,
+            "ALL" => TaxRule::All,
+            _ => return None,
+        };
+        let suicide_allowed = match field("sui")? {
+            "1" => true,
+            "0" => false,
+            _ => return None,
+        };
+        let handicap_bonus = match field("whb") {
+            Some("0") => HandicapBonus::Zero,
+            Some("N-1") => HandicapBonus::NMinusOne,
+            Some("N") => HandicapBonus::N,
+            None => HandicapBonus::Zero,
+            Some(_) => return None,
+        };
+
+        Some(Rules {
+            ko_rule,
+            suicide_allowed,
+            scoring,
+            tax,
+            handicap_bonus,
+            name: None,
+        })
+    }
+}
+
+#[uniffi::export]
+pub fn rules_for_rule_set(rule_set: RuleSet) -> Rules {
+    rule_set.rules()
+}
+
+/// Parses an SGF `RU` value, returning `None` for unknown rule names.
+#[uniffi::export]
+pub fn parse_rules(value: String) -> Option<Rules> {
+    Rules::from_sgf(&value)
+}
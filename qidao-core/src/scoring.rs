@@ -0,0 +1,250 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use crate::rules::{HandicapBonus, Rules, ScoringType, TaxRule};
+use crate::{Board, Point, StoneColor};
+
+/// Final count of a position, reported under both area and territory scoring.
+#[derive(uniffi::Record, Clone, Debug)]
+pub struct ScoreReport {
+    pub black_territory: Vec<Point>,
+    pub white_territory: Vec<Point>,
+    /// Empty points bordered by both colors (dame and seki liberties).
+    pub neutral_points: Vec<Point>,
+    pub dead_stones: Vec<Point>,
+    pub black_stones: u32,
+    pub white_stones: u32,
+    /// Stones captured during play plus dead stones removed at the end.
+    pub black_prisoners: u32,
+    pub white_prisoners: u32,
+    pub komi: f64,
+    /// Extra points White receives for handicap stones under area scoring.
+    pub handicap_bonus: f64,
+    pub black_area_score: f64,
+    pub white_area_score: f64,
+    pub black_territory_score: f64,
+    pub white_territory_score: f64,
+    /// Black minus White under the scoring type of the rules in effect.
+    pub margin: f64,
+    /// Result in SGF `RE` format ("B+3.5", "W+0.5", "0").
+    pub result: String,
+}
+
+/// An interactive end-of-game count over a fixed board.
+/// Dead stones are marked per chain; every change yields a fresh report.
+#[derive(uniffi::Object)]
+pub struct ScoringSession {
+    board: Arc<Board>,
+    rules: Rules,
+    komi: f64,
+    handicap: u32,
+    dead: Mutex<HashSet<usize>>,
+}
+
+#[uniffi::export]
+impl ScoringSession {
+    #[uniffi::constructor]
+    pub fn new(board: Arc<Board>, rules: Rules, komi: f64, handicap: u32) -> Arc<Self> {
+        Arc::new(Self {
+            board,
+            rules,
+            komi,
+            handicap,
+            dead: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Flips the dead/alive status of the chain at (x, y). Empty points are ignored.
+    pub fn toggle_dead_group(&self, x: u32, y: u32) -> ScoreReport {
+        if self.board.get_stone(x, y).is_some() {
+            let idx = (y * self.board.width + x) as usize;
+            let mut dead = self.dead.lock().unwrap();
+            if dead.contains(&idx) {
+                for i in self.board.chain(idx) {
+                    dead.remove(&i);
+                }
+            } else {
+                dead.extend(self.board.chain(idx));
+            }
+        }
+        self.score()
+    }
+
+    /// Replaces the dead-stone set. Points without stones are ignored.
+    pub fn set_dead_stones(&self, points: Vec<Point>) -> ScoreReport {
+        {
+            let mut dead = self.dead.lock().unwrap();
+            dead.clear();
+            for p in points {
+                if self.board.get_stone(p.x, p.y).is_some() {
+                    dead.insert((p.y * self.board.width + p.x) as usize);
+                }
+            }
+        }
+        self.score()
+    }
+
+    pub fn is_dead(&self, x: u32, y: u32) -> bool {
+        x < self.board.width
+            && y < self.board.height
+            && self.dead.lock().unwrap().contains(&((y * self.board.width + x) as usize))
+    }
+
+    pub fn get_dead_stones(&self) -> Vec<Point> {
+        let dead = self.dead.lock().unwrap();
+        let mut points: Vec<Point> = dead.iter().map(|&i| self.point(i)).collect();
+        points.sort_by_key(|p| (p.y, p.x));
+        points
+    }
+
+    pub fn score(&self) -> ScoreReport {
+        let board = &self.board;
+        let dead = self.dead.lock().unwrap().clone();
+        let alive = |i: usize| if dead.contains(&i) { None } else { board.grid[i] };
+
+        let mut black_territory = Vec::new();
+        let mut white_territory = Vec::new();
+        let mut neutral_points = Vec::new();
+        let mut visited = vec![false; board.grid.len()];
+
+        // Under seki taxation, regions touching the liberties of a chain in seki (shared
+        // liberties and the eyes of seki groups) belong to neither side
+        let seki_points: HashSet<usize> = match self.rules.tax {
+            TaxRule::Seki => board
+                .seki_chains(&board.unconditionally_alive())
+                .into_iter()
+                .filter(|h| !dead.contains(h))
+                .flat_map(|h| board.liberties(h))
+                .collect(),
+            TaxRule::None | TaxRule::All => HashSet::new(),
+        };
+
+        // Flood-fill regions of empty points and dead stones, noting which live colors border them
+        for start in 0..board.grid.len() {
+            if visited[start] || alive(start).is_some() {
+                continue;
+            }
+            let mut region = Vec::new();
+            let mut borders_black = false;
+            let mut borders_white = false;
+            let mut in_seki = false;
+            let mut stack = vec![start];
+            visited[start] = true;
+            while let Some(i) = stack.pop() {
+                region.push(self.point(i));
+                in_seki |= seki_points.contains(&i);
+                for n in board.adjacent(i) {
+                    match alive(n) {
+                        Some(StoneColor::Black) => borders_black = true,
+                        Some(StoneColor::White) => borders_white = true,
+                        None if !visited[n] => {
+                            visited[n] = true;
+                            stack.push(n);
+                        }
+                        None => {}
+                    }
+                }
+            }
+            match (borders_black, borders_white) {
+                _ if in_seki => neutral_points.extend(region),
+                (true, false) => black_territory.extend(region),
+                (false, true) => white_territory.extend(region),
+                _ => neutral_points.extend(region),
+            }
+        }
+
+        let mut black_stones = 0;
+        let mut white_stones = 0;
+        let mut dead_black = 0;
+        let mut dead_white = 0;
+        for (i, stone) in board.grid.iter().enumerate() {
+            match (stone, dead.contains(&i)) {
+                (Some(StoneColor::Black), false) => black_stones += 1,
+                (Some(StoneColor::White), false) => white_stones += 1,
+                (Some(StoneColor::Black), true) => dead_black += 1,
+                (Some(StoneColor::White), true) => dead_white += 1,
+                (None, _) => {}
+            }
+        }
+
+        // Territory points are only taxed under stone scoring: two eyes per living group
+        let (black_tax, white_tax) = match self.rules.tax {
+            TaxRule::All => (
+                2 * self.count_live_chains(StoneColor::Black, &dead),
+                2 * self.count_live_chains(StoneColor::White, &dead),
+            ),
+            TaxRule::None | TaxRule::Seki => (0, 0),
+        };
+        let black_points = (black_territory.len() as u32).saturating_sub(black_tax);
+        let white_points = (white_territory.len() as u32).saturating_sub(white_tax);
+
+        let black_prisoners = board.prisoners.black + dead_white;
+        let white_prisoners = board.prisoners.white + dead_black;
+
+        let handicap_bonus = match self.rules.handicap_bonus {
+            _ if self.handicap < 2 => 0.0,
+            HandicapBonus::Zero => 0.0,
+            HandicapBonus::NMinusOne => (self.handicap - 1) as f64,
+            HandicapBonus::N => self.handicap as f64,
+        };
+
+        let black_area_score = (black_stones + black_points) as f64;
+        let white_area_score = (white_stones + white_points) as f64 + self.komi + handicap_bonus;
+        let black_territory_score = (black_points + black_prisoners) as f64;
+        let white_territory_score = (white_points + white_prisoners) as f64 + self.komi;
+
+        let margin = match self.rules.scoring {
+            ScoringType::Area => black_area_score - white_area_score,
+            ScoringType::Territory => black_territory_score - white_territory_score,
+        };
+
+        let mut dead_stones: Vec<Point> = dead.iter().map(|&i| self.point(i)).collect();
+        dead_stones.sort_by_key(|p| (p.y, p.x));
+
+        ScoreReport {
+            black_territory,
+            white_territory,
+            neutral_points,
+            dead_stones,
+            black_stones,
+            white_stones,
+            black_prisoners,
+            white_prisoners,
+            komi: self.komi,
+            handicap_bonus,
+            black_area_score,
+            white_area_score,
+            black_territory_score,
+            white_territory_score,
+            margin,
+            result: format_result(margin),
+        }
+    }
+}
+
+impl ScoringSession {
+    fn point(&self, idx: usize) -> Point {
+        Point {
+            x: idx as u32 % self.board.width,
+            y: idx as u32 / self.board.width,
+        }
+    }
+
+    fn count_live_chains(&self, color: StoneColor, dead: &HashSet<usize>) -> u32 {
+        let board = &self.board;
+        (0..board.grid.len())
+            .filter(|&i| board.grid[i] == Some(color) && !dead.contains(&i) && board.chains.head(i) == i)
+            .count() as u32
+    }
+}
+
+/// Formats a Black-minus-White margin the way the SGF `RE` property expects.
+pub fn format_result(margin: f64) -> String {
+    if margin > 0.0 {
+        format!("B+{}", margin)
+    } else if margin < 0.0 {
+        format!("W+{}", -margin)
+    } else {
+        "0".to_string()
+    }
+}
@@ -0,0 +1,479 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::properties::{is_typed_identifier, typed_property, POINT_PROPERTIES};
+use crate::{
+    parse_board_size, repeats_position, turn_after, Board, Game, Legality, Move, Property, Rules, SgfNode, SgfProperty,
+    StoneColor, SuperkoPolicy,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum Severity {
+    /// The file breaks the SGF format or the rules of go.
+    Error,
+    /// The file loads, but something in it is questionable or was kept verbatim.
+    Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum DiagnosticKind {
+    /// Text that is not valid SGF syntax.
+    Syntax,
+    /// The input ends inside a value or before every game tree is closed.
+    Truncated,
+    /// A node with more than one B/W property.
+    DuplicateMove,
+    /// Any other property given twice in one node.
+    DuplicateProperty,
+    /// A point that is off the board or not two letters.
+    BadCoordinate,
+    /// A value that does not fit the property's type; it is kept verbatim.
+    WrongPropertyType,
+    /// A move on a point that already holds a stone.
+    OccupiedPoint,
+    /// A move the rules forbid for another reason: suicide, ko or superko.
+    IllegalMove { reason: Legality },
+}
+
+#[derive(uniffi::Record, Clone, Debug)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub severity: Severity,
+    pub message: String,
+    /// Byte offset in the validated text; `None` for games checked in memory.
+    pub offset: Option<u64>,
+    /// Index of the game tree within the file.
+    pub game: u32,
+    /// Child indices from the game's root down to the node; empty for the root.
+    pub node_path: Vec<u32>,
+    pub property: Option<String>,
+}
+
+/// Text inserted so that a damaged file loads.
+#[derive(uniffi::Record, Clone, Debug)]
+pub struct Repair {
+    pub offset: u64,
+    pub inserted: String,
+    pub description: String,
+}
+
+#[derive(uniffi::Record, Clone, Debug, Default)]
+pub struct ValidationReport {
+    pub diagnostics: Vec<Diagnostic>,
+    /// Repairs `parse_sgf` applies when loading the text; empty if it loads as written.
+    pub repairs: Vec<Repair>,
+    /// Whether the loaded games differ from the file because of repairs.
+    pub altered: bool,
+    /// Whether any move breaks the rules. Boards skip such moves, so the position shown
+    /// after them differs from what the file records; superko repetitions are the exception
+    /// and are played as recorded.
+    pub has_illegal_moves: bool,
+}
+
+/// Checks every game tree in `sgf_content` without loading it. Offsets are byte offsets
+/// into `sgf_content`.
+#[uniffi::export]
+pub fn validate_sgf(sgf_content: String) -> ValidationReport {
+    let scan = Scanner::new(&sgf_content).scan();
+    let mut report = ValidationReport { diagnostics: scan.diagnostics, repairs: scan.repairs, ..Default::default() };
+    for (game, &root) in scan.roots.iter().enumerate() {
+        let (width, height, rules) = game_settings(&scan.nodes[root].properties);
+        let mut checker = Checker {
+            width,
+            height,
+            ko_rule: rules.ko_rule,
+            game: game as u32,
+            diagnostics: &mut report.diagnostics,
+        };
+        checker.check_tree(&scan.nodes, root, vec![], Board::empty(width, height, rules), StoneColor::Black, &mut vec![]);
+    }
+    finish(report)
+}
+
+#[uniffi::export]
+impl Game {
+    /// Checks the game tree as it is in memory: property values, duplicates and legality of
+    /// every move in every variation. Diagnostics carry no offsets.
+    pub fn validate(&self) -> ValidationReport {
+        let state = self.state.lock().unwrap();
+        let mut nodes = Vec::new();
+        let root = flatten(&state.root, &mut nodes);
+        let mut report = ValidationReport::default();
+        let mut checker = Checker {
+            width: state.width,
+            height: state.height,
+            ko_rule: state.rules.ko_rule,
+            game: 0,
+            diagnostics: &mut report.diagnostics,
+        };
+        let board = Board::empty(state.width, state.height, state.rules);
+        checker.check_tree(&nodes, root, vec![], board, StoneColor::Black, &mut vec![]);
+        finish(report)
+    }
+}
+
+/// Text to append to a damaged file so that it parses: closes an unterminated value and
+/// every open game tree. `None` when nothing is missing at the end.
+pub(crate) fn truncation_repair(text: &str) -> Option<String> {
+    let repairs = Scanner::new(text).scan().repairs;
+    (!repairs.is_empty()).then(|| repairs.into_iter().map(|r| r.inserted).collect())
+}
+
+fn reason_text(reason: Legality) -> &'static str {
+    match reason {
+        Legality::Legal => "legal",
+        Legality::OutOfBounds => "off the board",
+        Legality::Occupied => "the point is occupied",
+        Legality::Suicide => "suicide",
+        Legality::Ko => "it retakes a ko",
+        Legality::Superko => "it repeats an earlier position",
+    }
+}
+
+fn finish(mut report: ValidationReport) -> ValidationReport {
+    report.altered = !report.repairs.is_empty();
+    report.has_illegal_moves = report
+        .diagnostics
+        .iter()
+        .any(|d| matches!(d.kind, DiagnosticKind::OccupiedPoint | DiagnosticKind::IllegalMove { .. }));
+    report
+}
+
+/// Board size and rules from a root's properties, with the same fallbacks as `Game`.
+fn game_settings(properties: &[(SgfProperty, Option<usize>)]) -> (u32, u32, Rules) {
+    let value = |id: &str| properties.iter().find(|(p, _)| p.identifier == id).and_then(|(p, _)| p.values.first());
+    let (width, height) = value("SZ").and_then(|v| parse_board_size(v)).unwrap_or((19, 19));
+    let rules = value("RU").and_then(|v| Rules::from_sgf(v)).unwrap_or_default();
+    (width, height, rules)
+}
+
+/// A node reduced to what the checks need, from either the scanner or a loaded tree.
+struct CheckNode {
+    /// Properties with the byte offset of their identifier, if known.
+    properties: Vec<(SgfProperty, Option<usize>)>,
+    offset: Option<usize>,
+    children: Vec<usize>,
+}
+
+fn flatten(node: &Arc<SgfNode>, nodes: &mut Vec<CheckNode>) -> usize {
+    let index = nodes.len();
+    let properties = node.get_properties().into_iter().map(|p| (p, None)).collect();
+    nodes.push(CheckNode { properties, offset: None, children: vec![] });
+    for child in node.get_children() {
+        let child_index = flatten(&child, nodes);
+        nodes[index].children.push(child_index);
+    }
+    index
+}
+
+struct Checker<'a> {
+    width: u32,
+    height: u32,
+    ko_rule: SuperkoPolicy,
+    game: u32,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    /// Checks `index` and everything below it. `board` is the position before the node,
+    /// `to_move` the side to play in it and `history` the hashes along the path, as `Game`
+    /// keeps them for superko.
+    fn check_tree(
+        &mut self,
+        nodes: &[CheckNode],
+        index: usize,
+        path: Vec<u32>,
+        board: Arc<Board>,
+        to_move: StoneColor,
+        history: &mut Vec<(u64, StoneColor)>,
+    ) {
+        let node = &nodes[index];
+        let mut board = board;
+        let mut seen = HashSet::new();
+        let mut moves = 0;
+        for (prop, offset) in &node.properties {
+            let id = prop.identifier.as_str();
+            let report = |checker: &mut Self, kind, severity, message: String| {
+                checker.push(kind, severity, message, offset.or(node.offset), &path, Some(id));
+            };
+
+            if id == "B" || id == "W" {
+                moves += 1;
+                if moves > 1 {
+                    report(self, DiagnosticKind::DuplicateMove, Severity::Error, "More than one move in a node".into());
+                }
+            } else if !seen.insert(id) {
+                report(self, DiagnosticKind::DuplicateProperty, Severity::Warning, format!("{} given twice", id));
+            }
+
+            let typed = typed_property(prop.clone(), self.width, self.height);
+            if matches!(typed, Property::Unknown { .. }) && is_typed_identifier(id) {
+                if POINT_PROPERTIES.contains(&id) {
+                    let message = format!("{}{:?} is not on the {}x{} board", id, prop.values, self.width, self.height);
+                    report(self, DiagnosticKind::BadCoordinate, Severity::Error, message);
+                } else {
+                    report(self, DiagnosticKind::WrongPropertyType, Severity::Warning, format!("Malformed {}{:?}", id, prop.values));
+                }
+                continue;
+            }
+
+            // Replay like `Game` does, so the diagnostics match what the board shows: superko
+            // is only enforced for new moves, so a recorded repetition is reported and played
+            match typed {
+                Property::Move { mv: Move::Pass, .. } => board = board.pass(),
+                Property::Move { color, mv: Move::Play { point } } => {
+                    match board.try_play(point.x, point.y, color) {
+                        Ok(result) => {
+                            if repeats_position(self.ko_rule, history, &result.board, color) {
+                                let message = format!("{} is illegal: {}", id, reason_text(Legality::Superko));
+                                let reason = Legality::Superko;
+                                report(self, DiagnosticKind::IllegalMove { reason }, Severity::Error, message);
+                            }
+                            board = result.board;
+                        }
+                        Err(Legality::Occupied) => {
+                            let message = format!("{} plays on an occupied point", id);
+                            report(self, DiagnosticKind::OccupiedPoint, Severity::Error, message);
+                        }
+                        Err(reason) => {
+                            let message = format!("{} is illegal: {}", id, reason_text(reason));
+                            report(self, DiagnosticKind::IllegalMove { reason }, Severity::Error, message);
+                        }
+                    }
+                }
+                Property::Setup { color, points } => {
+                    for p in points {
+                        board = board.with_stone(p.x, p.y, color);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let properties: Vec<SgfProperty> = node.properties.iter().map(|(p, _)| p.clone()).collect();
+        let to_move = turn_after(&properties, path.is_empty(), to_move);
+        history.push((board.hash, to_move));
+        for (i, &child) in node.children.iter().enumerate() {
+            let mut child_path = path.clone();
+            child_path.push(i as u32);
+            self.check_tree(nodes, child, child_path, board.clone(), to_move, history);
+        }
+        history.pop();
+    }
+
+    fn push(
+        &mut self,
+        kind: DiagnosticKind,
+        severity: Severity,
+        message: String,
+        offset: Option<usize>,
+        path: &[u32],
+        property: Option<&str>,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            kind,
+            severity,
+            message,
+            offset: offset.map(|o| o as u64),
+            game: self.game,
+            node_path: path.to_vec(),
+            property: property.map(str::to_string),
+        });
+    }
+}
+
+struct Scan {
+    nodes: Vec<CheckNode>,
+    roots: Vec<usize>,
+    diagnostics: Vec<Diagnostic>,
+    repairs: Vec<Repair>,
+}
+
+/// Reads the tree structure of SGF text while keeping byte offsets. It recovers from errors
+/// by skipping what it cannot read, so one problem does not hide the rest.
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+    nodes: Vec<CheckNode>,
+    /// (game, path) of each node, for diagnostics raised while scanning
+    locations: Vec<(u32, Vec<u32>)>,
+    roots: Vec<usize>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str) -> Self {
+        Scanner { text, pos: 0, nodes: vec![], locations: vec![], roots: vec![], diagnostics: vec![] }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn scan(mut self) -> Scan {
+        // For each open game tree, the node its first node hangs from
+        let mut open: Vec<Option<usize>> = Vec::new();
+        let mut last: Option<usize> = None;
+        let mut repairs = Vec::new();
+
+        while let Some(c) = self.peek() {
+            let start = self.pos;
+            match c {
+                '(' => {
+                    self.bump();
+                    open.push(last);
+                }
+                ')' => {
+                    self.bump();
+                    match open.pop() {
+                        Some(parent) => last = parent,
+                        None => self.syntax(start, last, "Unmatched ')'"),
+                    }
+                }
+                ';' => {
+                    self.bump();
+                    if open.is_empty() {
+                        self.syntax(start, last, "Node outside a game tree");
+                        continue;
+                    }
+                    last = Some(self.add_node(start, last));
+                }
+                'A'..='Z' => {
+                    let name: String = self.text[start..]
+                        .chars()
+                        .take_while(|c| c.is_ascii_alphabetic())
+                        .collect();
+                    self.pos += name.len();
+                    // FF[3] allows lower-case letters in identifiers ("AddBlack"); the loader drops them
+                    let identifier: String = name.chars().filter(char::is_ascii_uppercase).collect();
+                    let mut values = Vec::new();
+                    loop {
+                        while self.peek().is_some_and(char::is_whitespace) {
+                            self.bump();
+                        }
+                        if self.peek() != Some('[') {
+                            break;
+                        }
+                        self.bump();
+                        let (value, closed) = self.value();
+                        values.push(value);
+                        if !closed {
+                            repairs.push(Repair {
+                                offset: self.text.len() as u64,
+                                inserted: "]".to_string(),
+                                description: format!("Closed the unterminated {} value", identifier),
+                            });
+                            self.truncated(start, last, format!("Input ends inside a {} value", identifier));
+                        }
+                    }
+                    match last {
+                        _ if values.is_empty() => {
+                            self.syntax(start, last, &format!("{} has no value", identifier))
+                        }
+                        Some(node) if !open.is_empty() => self.nodes[node]
+                            .properties
+                            .push((SgfProperty { identifier, values }, Some(start))),
+                        _ => self.syntax(start, last, &format!("{} is outside a node", identifier)),
+                    }
+                }
+                c if c.is_whitespace() => {
+                    self.bump();
+                }
+                _ => {
+                    // Skip the whole run of junk, values included, and report it once
+                    while let Some(c) = self.peek().filter(|c| !matches!(c, '(' | ')' | ';' | 'A'..='Z')) {
+                        self.bump();
+                        if c == '[' {
+                            self.value();
+                        }
+                    }
+                    let junk = self.text[start..self.pos].trim_end();
+                    self.syntax(start, last, &format!("Unexpected text '{}'", junk));
+                }
+            }
+        }
+
+        if !open.is_empty() {
+            let end = self.text.len();
+            repairs.push(Repair {
+                offset: end as u64,
+                inserted: ")".repeat(open.len()),
+                description: format!("Closed {} open game tree(s)", open.len()),
+            });
+            self.truncated(end, last, format!("Input ends with {} game tree(s) still open", open.len()));
+        }
+
+        Scan { nodes: self.nodes, roots: self.roots, diagnostics: self.diagnostics, repairs }
+    }
+
+    /// Reads a value after its '['. The flag is false when the input ends before the ']'.
+    fn value(&mut self) -> (String, bool) {
+        let mut value = String::new();
+        while let Some(c) = self.bump() {
+            match c {
+                ']' => return (value, true),
+                '\\' => match self.bump() {
+                    // Soft line break
+                    Some(first @ ('\n' | '\r')) => {
+                        if self.peek().is_some_and(|c| matches!(c, '\n' | '\r') && c != first) {
+                            self.bump();
+                        }
+                    }
+                    Some(escaped) => value.push(escaped),
+                    None => break,
+                },
+                c => value.push(c),
+            }
+        }
+        (value, false)
+    }
+
+    fn add_node(&mut self, offset: usize, parent: Option<usize>) -> usize {
+        let index = self.nodes.len();
+        let location = match parent {
+            Some(p) => {
+                let (game, mut path) = self.locations[p].clone();
+                path.push(self.nodes[p].children.len() as u32);
+                self.nodes[p].children.push(index);
+                (game, path)
+            }
+            None => {
+                self.roots.push(index);
+                (self.roots.len() as u32 - 1, vec![])
+            }
+        };
+        self.nodes.push(CheckNode { properties: vec![], offset: Some(offset), children: vec![] });
+        self.locations.push(location);
+        index
+    }
+
+    fn syntax(&mut self, offset: usize, node: Option<usize>, message: &str) {
+        self.report(DiagnosticKind::Syntax, Severity::Error, offset, node, message.to_string());
+    }
+
+    fn truncated(&mut self, offset: usize, node: Option<usize>, message: String) {
+        self.report(DiagnosticKind::Truncated, Severity::Warning, offset, node, message);
+    }
+
+    fn report(&mut self, kind: DiagnosticKind, severity: Severity, offset: usize, node: Option<usize>, message: String) {
+        let (game, node_path) = match node {
+            Some(n) => self.locations[n].clone(),
+            None => (self.roots.len() as u32, vec![]),
+        };
+        self.diagnostics.push(Diagnostic {
+            kind,
+            severity,
+            message,
+            offset: Some(offset as u64),
+            game,
+            node_path,
+            property: None,
+        });
+    }
+}